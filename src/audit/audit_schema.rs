@@ -0,0 +1,56 @@
+use super::audit_methods::epoch_seconds;
+use super::{core, DateTime, Deserialize, Extra, FmcClient, Serialize, Utc};
+
+// /audit/auditrecords
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    /// Sent by FMC as seconds since the epoch.
+    #[serde(with = "epoch_seconds")]
+    pub time: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// The FMC area the change was made in, e.g. "Login" or "Object Manager".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsystem: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// IP address the user connected from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Narrows audit records by time, user, subsystem or source. Empty
+/// matches everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditFilter {
+    pub(super) start_time: Option<DateTime<Utc>>,
+    pub(super) end_time: Option<DateTime<Utc>>,
+    pub(super) username: Option<String>,
+    pub(super) subsystem: Option<String>,
+    pub(super) source: Option<String>,
+}
+
+/// Where the last Audit::since() call stopped. Serializable, so it can
+/// be saved between runs of a log shipper.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AuditCursor {
+    /// Time of the newest record returned so far, in epoch seconds.
+    pub(super) last_time: Option<i64>,
+    /// IDs of the records returned with exactly that time. FMC times have
+    /// one-second resolution, so the next poll starts at last_time again
+    /// and skips these.
+    pub(super) seen_ids: Vec<String>,
+}
+
+/// Audit trail: /audit/auditrecords
+#[derive(Debug)]
+pub struct Audit<'c> {
+    pub(super) client: &'c FmcClient,
+}
@@ -0,0 +1,10 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra};
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::{Filter, FmcApi};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+mod audit_methods;
+pub mod audit_schema;
@@ -0,0 +1,417 @@
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::client_schema::{Devices, FmcClient, Paginator};
+use super::MAX_PAGE_SIZE;
+use super::{collect_body, decode, devices, Collection, Filter, FmcApi, FmcError, FmcQuery};
+use super::{Body, Client, FmcConnector, RateLimit, RateLimiter, TlsConfig};
+use super::{FmcRequest, ObjectList, RetryPolicy, SessionCreds, Transport};
+use super::{ServerVersionItem, ServerVersions};
+
+impl FmcClient {
+    /// Creates a client for the FMC at `host`. No request is made until
+    /// login() or the first API call. `host` is a name or address on port
+    /// 443, or a base URL such as "https://fmc.example.com:8443".
+    pub async fn new(host: &str, username: &str, password: &str) -> Result<FmcClient, FmcError> {
+        let connector = FmcConnector::new()?;
+        Ok(FmcClient::with_connector(host, username, password, connector).await)
+    }
+
+    /// Like new(), verifying the FMC's certificate per `tls`, e.g. for a
+    /// self-signed FMC: TlsConfig::new().pin_sha256("AB:CD:...")?
+    pub async fn with_tls(
+        host: &str,
+        username: &str,
+        password: &str,
+        tls: TlsConfig,
+    ) -> Result<FmcClient, FmcError> {
+        let connector = tls.connector()?;
+        Ok(FmcClient::with_connector(host, username, password, connector).await)
+    }
+
+    async fn with_connector(
+        host: &str,
+        username: &str,
+        password: &str,
+        connector: FmcConnector,
+    ) -> FmcClient {
+        let mut sess_creds = SessionCreds::new().await;
+        sess_creds.http_basic_auth(username, password).await;
+
+        FmcClient {
+            host: host.to_string(),
+            transport: Transport::new(Client::builder().build::<_, Body>(connector))
+                .limiter(Arc::new(RateLimiter::new(RateLimit::new()))),
+            sess_creds: Mutex::new(sess_creds),
+            versions: Mutex::new(None),
+        }
+    }
+
+    /// Replaces the default pacing (FMC's 120 requests per minute, 10 at
+    /// a time). Every request made through this client shares it.
+    pub fn rate_limit(mut self, limit: RateLimit) -> FmcClient {
+        self.transport.limiter = Some(Arc::new(RateLimiter::new(limit)));
+        self
+    }
+
+    /// Replaces the default ExponentialBackoff, e.g. with NoRetry or a
+    /// policy of your own. Token requests follow it too.
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, policy: P) -> FmcClient {
+        self.transport.retry = Arc::new(policy);
+        self
+    }
+
+    /// Generates an access token now rather than on the first request.
+    /// Also looks up the server version, so later calls can be checked
+    /// against it; login still succeeds if that lookup fails.
+    pub async fn login(&self) -> Result<(), FmcError> {
+        self.sess_creds
+            .lock()
+            .await
+            .ensure_token(&self.transport, &self.host)
+            .await?;
+        let _ = self.server_version().await;
+        Ok(())
+    }
+
+    /// FMC, VDB, SRU and geolocation versions from /info/serverversion.
+    /// Fetched on first use and cached for the life of the client. A
+    /// failed lookup is remembered for request(), but calling this again
+    /// retries it.
+    pub async fn server_version(&self) -> Result<ServerVersions, FmcError> {
+        if let Some(Some(versions)) = &*self.versions.lock().await {
+            return Ok(versions.clone());
+        }
+
+        let fetched = self.fetch_server_version().await;
+        let mut versions = self.versions.lock().await;
+        match &fetched {
+            Ok(found) => *versions = Some(Some(found.clone())),
+            Err(_) if versions.is_none() => *versions = Some(None),
+            Err(_) => {}
+        }
+        fetched
+    }
+
+    async fn fetch_server_version(&self) -> Result<ServerVersions, FmcError> {
+        let resp = self
+            .session_request()
+            .await?
+            .get(FmcApi::ServerVersion)
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        let page: ObjectList<ServerVersionItem> = decode(&collect_body(resp).await?)?;
+        match page.items.first() {
+            Some(item) => ServerVersions::from_item(item),
+            None => Err(FmcError::MissingField("serverVersion")),
+        }
+    }
+
+    /// Whether this FMC is new enough to serve `api`.
+    pub async fn supports(&self, api: &FmcApi) -> Result<bool, FmcError> {
+        Ok(match api.min_version() {
+            Some(min) => self.server_version().await?.fmc.at_least(&min),
+            None => true,
+        })
+    }
+
+    /// Makes `domain` (a name or UUID, see SessionCreds::resolve_domain)
+    /// the default for every later request. Logs in first if needed.
+    pub async fn use_domain(&self, domain: &str) -> Result<(), FmcError> {
+        let mut sess_creds = self.sess_creds.lock().await;
+        sess_creds.ensure_token(&self.transport, &self.host).await?;
+        let uuid = sess_creds.resolve_domain(domain)?;
+        sess_creds.select_domain(uuid);
+        Ok(())
+    }
+
+    /// A copy of the current session, e.g. to inspect the domain UUID.
+    pub async fn session(&self) -> SessionCreds {
+        self.sess_creds.lock().await.clone()
+    }
+
+    pub fn devices(&self) -> Devices<'_> {
+        Devices { client: self }
+    }
+
+    /// Pages through a collection endpoint, e.g.
+    /// client.paginate::<devices::DeviceRecords>(FmcApi::Devices)
+    pub fn paginate<T: Collection>(&self, api: FmcApi) -> Paginator<'_, T> {
+        Paginator {
+            client: self,
+            api,
+            page_size: MAX_PAGE_SIZE,
+            expanded: false,
+            filters: Vec::new(),
+            page: PhantomData,
+        }
+    }
+
+    /// Starts a builder chain with a fresh token and the shared transport.
+    /// The session lock is only held while the token is checked, not while
+    /// the request is in flight. Endpoints newer than the FMC are refused
+    /// with FmcError::UnsupportedVersion. The version is looked up once;
+    /// if that fails (e.g. the API user may not read it), nothing is
+    /// refused and it isn't asked for again.
+    pub async fn request(&self) -> Result<FmcRequest<'_>, FmcError> {
+        let known = self.versions.lock().await.clone();
+        let version = match known {
+            Some(outcome) => outcome,
+            None => self.server_version().await.ok(),
+        };
+        let req = self.session_request().await?;
+        Ok(match version {
+            Some(versions) => req.server_version(versions.fmc),
+            None => req,
+        })
+    }
+
+    async fn session_request(&self) -> Result<FmcRequest<'_>, FmcError> {
+        let sess_creds = {
+            let mut sess_creds = self.sess_creds.lock().await;
+            sess_creds.ensure_token(&self.transport, &self.host).await?;
+            sess_creds.clone()
+        };
+
+        Ok(FmcRequest::with_session(self.transport.clone(), sess_creds)
+            .await
+            .host(&self.host)
+            .await)
+    }
+
+    /// Like request(), addressing `domain` instead of the current domain.
+    pub async fn request_in(&self, domain: &str) -> Result<FmcRequest<'_>, FmcError> {
+        self.request().await?.domain(domain).await
+    }
+
+    /// GETs any endpoint and decodes the reply into `T`.
+    pub async fn get_json<T: DeserializeOwned>(&self, api: FmcApi) -> Result<T, FmcError> {
+        let resp = self
+            .request()
+            .await?
+            .get(api)
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        decode(&collect_body(resp).await?)
+    }
+
+    /// POSTs `body` as JSON and decodes the reply into `T`.
+    pub async fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        api: FmcApi,
+        query: FmcQuery,
+        body: &B,
+    ) -> Result<T, FmcError> {
+        let resp = self
+            .request()
+            .await?
+            .post(api)
+            .await?
+            .query(query)
+            .await
+            .json(body)
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        decode(&collect_body(resp).await?)
+    }
+
+    /// PUTs `body` as JSON and decodes the reply into `T`.
+    pub async fn put_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        api: FmcApi,
+        body: &B,
+    ) -> Result<T, FmcError> {
+        let resp = self
+            .request()
+            .await?
+            .put(api)
+            .await?
+            .json(body)
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        decode(&collect_body(resp).await?)
+    }
+
+    /// DELETEs an object. FMC answers with the object it removed.
+    pub async fn delete_json<T: DeserializeOwned>(&self, api: FmcApi) -> Result<T, FmcError> {
+        let resp = self
+            .request()
+            .await?
+            .delete(api)
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        decode(&collect_body(resp).await?)
+    }
+}
+
+impl<'c> Devices<'c> {
+    /// The first page of device records.
+    pub async fn list(&self) -> Result<devices::DeviceRecords, FmcError> {
+        self.client.get_json(FmcApi::Devices).await
+    }
+
+    /// Every device record, page by page.
+    pub fn paginate(&self) -> Paginator<'c, devices::DeviceRecords> {
+        self.client.paginate(FmcApi::Devices)
+    }
+
+    /// Every device with the full expanded record (model, health,
+    /// licenses, policies, inventory), for asset reports.
+    pub async fn inventory(&self) -> Result<Vec<devices::DeviceItem>, FmcError> {
+        self.paginate().expanded(true).collect_all().await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<devices::DeviceItem, FmcError> {
+        self.client.get_json(FmcApi::device(id).record()).await
+    }
+
+    pub fn physical_interfaces(&self, id: &str) -> Paginator<'c, devices::PhysicalInterfaces> {
+        self.client
+            .paginate(FmcApi::device(id).physical_interfaces())
+    }
+
+    pub fn etherchannel_interfaces(&self, id: &str) -> Paginator<'c, devices::EtherChannelInts> {
+        self.client
+            .paginate(FmcApi::device(id).etherchannel_interfaces())
+    }
+}
+
+impl<'c, T: Collection + 'c> Paginator<'c, T> {
+    /// Items per request, clamped to FMC's 1..=1000.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Asks FMC for the full object on every item instead of just links.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Narrows every page with a filter clause.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Fetches the single page starting at `offset`.
+    pub async fn page(&self, offset: u32) -> Result<T, FmcError> {
+        let mut query = FmcQuery::new().offset(offset).limit(self.page_size);
+        if self.expanded {
+            query = query.expanded(true);
+        }
+        for filter in &self.filters {
+            query = query.filter(filter.clone());
+        }
+
+        let resp = self
+            .client
+            .request()
+            .await?
+            .get(self.api.clone())
+            .await?
+            .query(query)
+            .await
+            .build()
+            .await?
+            .send()
+            .await?;
+        decode(&collect_body(resp).await?)
+    }
+
+    /// Yields every item of the collection, fetching pages as it goes.
+    pub fn stream(self) -> impl Stream<Item = Result<T::Item, FmcError>> + 'c {
+        stream::try_unfold((self, Some(0)), |(this, offset)| async move {
+            let offset = match offset {
+                Some(offset) => offset,
+                None => return Ok::<_, FmcError>(None),
+            };
+
+            let page = this.page(offset).await?;
+            let count = page.paging().count;
+            let items = page.into_items();
+
+            let next = offset + this.page_size;
+            let next = if items.is_empty() || next >= count {
+                None
+            } else {
+                Some(next)
+            };
+
+            let items = stream::iter(items.into_iter().map(Ok));
+            Ok(Some((items, (this, next))))
+        })
+        .try_flatten()
+    }
+
+    /// Fetches every page and returns all items.
+    pub async fn collect_all(self) -> Result<Vec<T::Item>, FmcError> {
+        self.stream().try_collect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::retry::retry_schema::NoRetry;
+    use super::*;
+
+    #[tokio::test]
+    async fn fn_unreachable_host() {
+        let client = FmcClient::new("127.0.0.1", "apiuser", "password")
+            .await
+            .unwrap()
+            .retry_policy(NoRetry);
+        let err = client.devices().list().await;
+
+        assert!(matches!(err, Err(FmcError::Transport(_))));
+        assert_eq!(client.session().await.dom_uuid(), None);
+    }
+
+    #[tokio::test]
+    async fn fn_paginator_settings() {
+        let client = FmcClient::new("127.0.0.1", "apiuser", "password")
+            .await
+            .unwrap();
+
+        let pages = client.devices().paginate();
+        assert_eq!(pages.page_size, MAX_PAGE_SIZE);
+        assert!(!pages.expanded);
+
+        let pages = pages.page_size(0).expanded(true);
+        assert_eq!(pages.page_size, 1);
+        assert!(pages.expanded);
+        assert_eq!(pages.page_size(5000).page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn fn_empty_collection() {
+        let page: devices::DeviceRecords = decode(
+            r#"{"links": {"self": "https://fmc/devicerecords?offset=0&limit=25"},
+                "paging": {"offset": 0, "limit": 25, "count": 0, "pages": 0}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(page.paging().count, 0);
+        assert!(page.into_items().is_empty());
+    }
+}
@@ -0,0 +1,36 @@
+use super::{Filter, FmcApi, ServerVersions, SessionCreds, Transport};
+use std::marker::PhantomData;
+use tokio::sync::Mutex;
+
+/// A logged-in connection to one FMC.
+///
+/// The hyper client (and its connection pool), rate limiter, retry policy
+/// and session tokens are shared by every request made through it, so
+/// build one per FMC and keep it around.
+#[derive(Debug)]
+pub struct FmcClient {
+    pub(super) host: String,
+    pub(super) transport: Transport,
+    pub(super) sess_creds: Mutex<SessionCreds>,
+    /// Looked up once, by login() or the first request; Some(None) once
+    /// that lookup has failed.
+    pub(super) versions: Mutex<Option<Option<ServerVersions>>>,
+}
+
+/// Device records: /devices/devicerecords
+#[derive(Debug)]
+pub struct Devices<'c> {
+    pub(super) client: &'c FmcClient,
+}
+
+/// Walks every page of a collection endpoint, `T` being the page schema
+/// (e.g. devices::DeviceRecords). Build with FmcClient::paginate().
+#[derive(Debug)]
+pub struct Paginator<'c, T> {
+    pub(super) client: &'c FmcClient,
+    pub(super) api: FmcApi,
+    pub(super) page_size: u32,
+    pub(super) expanded: bool,
+    pub(super) filters: Vec<Filter>,
+    pub(super) page: PhantomData<T>,
+}
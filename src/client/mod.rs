@@ -0,0 +1,17 @@
+use super::error::error_schema::FmcError;
+use super::info::info_schema::{ServerVersionItem, ServerVersions};
+use super::json::decode;
+use super::json::json_schema::{core::Collection, devices};
+use super::limit::limit_schema::{RateLimit, RateLimiter};
+use super::objects::objects_schema::ObjectList;
+use super::retry::retry_schema::RetryPolicy;
+use super::session::collect_body;
+use super::session::session_schema::{FmcRequest, SessionCreds, Transport};
+use super::uri::uri_schema::{Filter, FmcApi, FmcQuery};
+use super::*;
+
+mod client_methods;
+pub mod client_schema;
+
+/// FMC rejects a limit above 1000 items per page.
+const MAX_PAGE_SIZE: u32 = 1000;
@@ -0,0 +1,244 @@
+use super::cluster_schema::{ClusterEntry, InventoryItem, NodeHealth, NodeRole};
+use super::cluster_schema::{ClusterHealth, ClusterMember, ClusterNode, Clusters, DeviceCluster};
+use super::{DeviceItem, FmcApi, FmcClient, FmcError, HealthStatus, ObjectList, Paginator};
+
+impl FmcClient {
+    pub fn clusters(&self) -> Clusters<'_> {
+        Clusters { client: self }
+    }
+}
+
+impl<'c> Clusters<'c> {
+    /// Every cluster, expanded so each lists its nodes.
+    pub fn list(&self) -> Paginator<'c, ObjectList<DeviceCluster>> {
+        self.client.paginate(FmcApi::DeviceClusters).expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<DeviceCluster, FmcError> {
+        self.client
+            .get_json(FmcApi::DeviceCluster(id.to_string()))
+            .await
+    }
+
+    /// Saves node settings, or which nodes belong to the cluster.
+    pub async fn update(&self, cluster: &DeviceCluster) -> Result<DeviceCluster, FmcError> {
+        self.client
+            .put_json(FmcApi::DeviceCluster(cluster.id.clone()), cluster)
+            .await
+    }
+
+    /// Takes a data node out of the cluster; it stays on FMC as a
+    /// standalone device. The control node can't be removed this way.
+    pub async fn remove_node(&self, id: &str, device_id: &str) -> Result<DeviceCluster, FmcError> {
+        let mut cluster = self.get(id).await?;
+        let before = cluster.data.len();
+        cluster.data.retain(|node| node.device.id != device_id);
+        if cluster.data.len() == before {
+            return Err(FmcError::NotFound(device_id.to_string()));
+        }
+        self.update(&cluster).await
+    }
+
+    /// Breaks the cluster and removes it and its nodes from FMC.
+    pub async fn delete(&self, id: &str) -> Result<DeviceCluster, FmcError> {
+        self.client
+            .delete_json(FmcApi::DeviceCluster(id.to_string()))
+            .await
+    }
+
+    /// The nodes' full device records, control first. GETs each node by
+    /// ID rather than listing every device.
+    pub async fn members(&self, cluster: &DeviceCluster) -> Result<Vec<ClusterMember>, FmcError> {
+        let mut members = Vec::new();
+        for (role, node) in cluster.nodes() {
+            let device = self.client.devices().get(&node.device.id).await?;
+            members.push(ClusterMember { role, device });
+        }
+        Ok(members)
+    }
+
+    /// Each node's health, read from its device record by ID.
+    pub async fn health(&self, cluster: &DeviceCluster) -> Result<ClusterHealth, FmcError> {
+        let mut nodes = Vec::new();
+        for (role, node) in cluster.nodes() {
+            let device = self.client.devices().get(&node.device.id).await?;
+            nodes.push(NodeHealth {
+                device: node.device.clone(),
+                role,
+                health: device.health_status,
+            });
+        }
+        Ok(ClusterHealth {
+            cluster: cluster.name.clone(),
+            nodes,
+        })
+    }
+
+    /// Every device, with cluster nodes grouped under their cluster
+    /// instead of listed on their own. Clusters come after standalone
+    /// devices.
+    pub async fn inventory(&self) -> Result<Vec<InventoryItem>, FmcError> {
+        let devices = self.client.devices().inventory().await?;
+        let clusters = self.list().collect_all().await?;
+
+        let clustered = |device: &DeviceItem| {
+            clusters
+                .iter()
+                .any(|cluster| cluster.nodes().any(|(_, node)| node.device.id == device.id))
+        };
+        let mut items: Vec<InventoryItem> = devices
+            .iter()
+            .filter(|device| !clustered(device))
+            .map(|device| InventoryItem::Device(Box::new(device.clone())))
+            .collect();
+        items.extend(clusters.into_iter().map(|cluster| {
+            InventoryItem::Cluster(Box::new(ClusterEntry {
+                members: cluster.members_in(&devices),
+                cluster,
+            }))
+        }));
+        Ok(items)
+    }
+}
+
+impl DeviceCluster {
+    /// Every node with its role, control first.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeRole, &ClusterNode)> {
+        let data = self.data.iter().map(|node| (NodeRole::Data, node));
+        std::iter::once((NodeRole::Control, &self.control)).chain(data)
+    }
+
+    /// Nodes whose device record is in `devices`, control first.
+    fn members_in(&self, devices: &[DeviceItem]) -> Vec<ClusterMember> {
+        self.nodes()
+            .filter_map(|(role, node)| {
+                let device = devices.iter().find(|device| device.id == node.device.id)?;
+                Some(ClusterMember {
+                    role,
+                    device: device.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl ClusterHealth {
+    /// The worst node health. A node with no or an unrecognised status
+    /// counts as worse than yellow but better than red.
+    pub fn overall(&self) -> HealthStatus {
+        let rank = |health: &Option<HealthStatus>| match health {
+            Some(HealthStatus::Green) => 0,
+            Some(HealthStatus::Yellow) => 1,
+            Some(HealthStatus::Red) => 3,
+            _ => 2,
+        };
+        let worst = self
+            .nodes
+            .iter()
+            .map(|node| &node.health)
+            .max_by_key(|h| rank(h));
+        match worst {
+            Some(Some(health)) => health.clone(),
+            Some(None) => HealthStatus::Other("unknown".to_string()),
+            None => HealthStatus::Green,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+    use serde_json::json;
+
+    fn device(id: &str, name: &str, health: &str) -> serde_json::Value {
+        json!({"id": id, "type": "Device", "name": name, "healthStatus": health})
+    }
+
+    #[test]
+    fn fn_parse_legacy_names() {
+        let cluster: DeviceCluster = serde_json::from_str(
+            r#"{"id": "cl-1", "type": "DeviceCluster", "name": "dc-cluster",
+                "masterDevice": {"deviceDetails": {"id": "dev-1", "type": "Device", "name": "unit-1-1"}},
+                "slaveDevices": [{"deviceDetails": {"id": "dev-2", "type": "Device", "name": "unit-1-2"},
+                                  "clusterNodeBootstrap": {"unitName": "unit-1-2", "priority": 2, "cclIp": "10.0.0.2"}}],
+                "ftdClusterBootstrap": {"clusterInterface": {"id": "po48", "type": "EtherChannelInterface"},
+                                        "cclNetwork": "10.0.0.0/24"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(cluster.control.device.id, "dev-1");
+        assert_eq!(
+            cluster.data[0].bootstrap.as_ref().unwrap().priority,
+            Some(2)
+        );
+        let roles: Vec<NodeRole> = cluster.nodes().map(|(role, _)| role).collect();
+        assert_eq!(roles, vec![NodeRole::Control, NodeRole::Data]);
+
+        let body = serde_json::to_value(&cluster).unwrap();
+        assert_eq!(body["controlDevice"]["deviceDetails"]["id"], "dev-1");
+        assert_eq!(
+            body["dataDevices"][0]["clusterNodeBootstrap"]["cclIp"],
+            "10.0.0.2"
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_cluster_inventory() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        mock.fixture(
+            "devices/devicerecords",
+            vec![
+                device("dev-0", "ftd-branch-1", "green"),
+                device("dev-1", "ftd-dc-1", "green"),
+                device("dev-2", "ftd-dc-2", "yellow"),
+                device("dev-3", "ftd-dc-3", "green"),
+            ],
+        );
+        let node = |id: &str| json!({"deviceDetails": {"id": id, "type": "Device"}});
+        mock.fixture(
+            "deviceclusters/ftddevicecluster",
+            vec![json!({
+                "id": "cl-1",
+                "type": "DeviceCluster",
+                "name": "dc-cluster",
+                "controlDevice": node("dev-1"),
+                "dataDevices": [node("dev-2"), node("dev-3")],
+            })],
+        );
+        let client = mock.client().await;
+        let clusters = client.clusters();
+
+        let inventory = clusters.inventory().await?;
+        assert_eq!(inventory.len(), 2);
+        assert!(matches!(&inventory[0], InventoryItem::Device(d) if d.name == "ftd-branch-1"));
+        match &inventory[1] {
+            InventoryItem::Cluster(entry) => {
+                assert_eq!(entry.cluster.name, "dc-cluster");
+                assert_eq!(entry.members.len(), 3);
+                assert_eq!(entry.members[0].role, NodeRole::Control);
+                assert_eq!(entry.members[0].device.name, "ftd-dc-1");
+            }
+            other => panic!("expected a cluster, got {:?}", other),
+        }
+
+        let listed = mock.requests().len();
+        let cluster = clusters.get("cl-1").await?;
+        assert_eq!(
+            clusters.health(&cluster).await?.overall(),
+            HealthStatus::Yellow
+        );
+        assert!(mock.requests()[listed..]
+            .iter()
+            .all(|r| !r.contains("/devicerecords?")));
+
+        let cluster = clusters.remove_node("cl-1", "dev-3").await?;
+        assert_eq!(cluster.data.len(), 1);
+        assert_eq!(clusters.members(&cluster).await?.len(), 2);
+        assert!(matches!(
+            clusters.remove_node("cl-1", "dev-1").await,
+            Err(FmcError::NotFound(_))
+        ));
+        Ok(())
+    }
+}
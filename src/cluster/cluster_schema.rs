@@ -0,0 +1,114 @@
+use super::{core, Deserialize, DeviceItem, Extra, FmcClient, HealthStatus, Reference, Serialize};
+
+// /deviceclusters/ftddevicecluster
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceCluster {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    pub name: String,
+    /// Called masterDevice before FMC 6.7.
+    #[serde(rename = "controlDevice", alias = "masterDevice")]
+    pub control: ClusterNode,
+    /// Called slaveDevices before FMC 6.7.
+    #[serde(rename = "dataDevices", alias = "slaveDevices", default)]
+    pub data: Vec<ClusterNode>,
+    #[serde(
+        rename = "ftdClusterBootstrap",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bootstrap: Option<ClusterBootstrap>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClusterNode {
+    /// The node's device record.
+    #[serde(rename = "deviceDetails")]
+    pub device: Reference,
+    #[serde(
+        rename = "clusterNodeBootstrap",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bootstrap: Option<NodeBootstrap>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeBootstrap {
+    #[serde(rename = "unitName", skip_serializing_if = "Option::is_none")]
+    pub unit_name: Option<String>,
+    /// Lower wins the control role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    /// The node's address on the cluster control link.
+    #[serde(rename = "cclIp", skip_serializing_if = "Option::is_none")]
+    pub ccl_ip: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClusterBootstrap {
+    /// The cluster control link interface.
+    #[serde(rename = "clusterInterface", skip_serializing_if = "Option::is_none")]
+    pub cluster_interface: Option<Reference>,
+    #[serde(rename = "cclNetwork", skip_serializing_if = "Option::is_none")]
+    pub ccl_network: Option<String>,
+    #[serde(rename = "clusterGroupName", skip_serializing_if = "Option::is_none")]
+    pub group_name: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRole {
+    Control,
+    Data,
+}
+
+/// A node's expanded device record and its role.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterMember {
+    pub role: NodeRole,
+    pub device: DeviceItem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeHealth {
+    pub device: Reference,
+    pub role: NodeRole,
+    /// None when FMC has no device record for the node.
+    pub health: Option<HealthStatus>,
+}
+
+/// Health of every node, control first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterHealth {
+    pub cluster: String,
+    pub nodes: Vec<NodeHealth>,
+}
+
+/// One line of a cluster-aware inventory. Boxed, as both records are large.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InventoryItem {
+    Device(Box<DeviceItem>),
+    Cluster(Box<ClusterEntry>),
+}
+
+/// A cluster and its nodes' device records, control first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterEntry {
+    pub cluster: DeviceCluster,
+    pub members: Vec<ClusterMember>,
+}
+
+/// FTD clusters, from client.clusters().
+#[derive(Debug)]
+pub struct Clusters<'c> {
+    pub(super) client: &'c FmcClient,
+}
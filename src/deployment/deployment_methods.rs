@@ -0,0 +1,437 @@
+use super::deployment_schema::{DeployOptions, DeployOutcome, DeployResult, DeployableDevice};
+use super::deployment_schema::{DeploymentRequest, Deployments, SubTask, TaskState, TaskStatus};
+use super::{Extra, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator, Reference};
+use std::time::Duration;
+use tokio::time::{delay_for, Instant};
+
+impl FmcClient {
+    pub fn deployments(&self) -> Deployments<'_> {
+        Deployments { client: self }
+    }
+}
+
+impl<'c> Deployments<'c> {
+    /// Devices with pending changes. Expanded so each carries its version.
+    pub fn deployable_devices(&self) -> Paginator<'c, ObjectList<DeployableDevice>> {
+        self.client
+            .paginate(FmcApi::DeployableDevices)
+            .expanded(true)
+    }
+
+    /// Starts a deployment. The reply's metadata.task identifies the task to poll.
+    pub async fn request(
+        &self,
+        request: &DeploymentRequest,
+    ) -> Result<DeploymentRequest, FmcError> {
+        self.client
+            .post_json(FmcApi::DeploymentRequests, FmcQuery::new(), request)
+            .await
+    }
+
+    pub async fn task_status(&self, task_id: &str) -> Result<TaskStatus, FmcError> {
+        self.client
+            .get_json(FmcApi::TaskStatus(task_id.to_string()))
+            .await
+    }
+
+    /// Deploys pending changes and waits for the task to finish.
+    /// `progress` sees every task status polled along the way. Requested
+    /// devices with nothing to deploy come back as NotDeployable.
+    pub async fn deploy<F>(
+        &self,
+        options: DeployOptions,
+        progress: F,
+    ) -> Result<Vec<DeployOutcome>, FmcError>
+    where
+        F: FnMut(&TaskStatus),
+    {
+        let deployable = self.deployable_devices().collect_all().await?;
+        let (targets, mut outcomes) = select_targets(deployable, options.devices.as_deref());
+        if targets.is_empty() {
+            return Ok(outcomes);
+        }
+
+        let request = DeploymentRequest::new(
+            &newest_version(&targets),
+            targets
+                .iter()
+                .map(|(device, _)| device.id.as_str())
+                .collect(),
+        )
+        .force_deploy(options.force_deploy)
+        .ignore_warning(options.ignore_warning);
+
+        let task = match self.request(&request).await?.metadata.and_then(|m| m.task) {
+            Some(task) => task,
+            None => return Err(FmcError::MissingField("metadata.task")),
+        };
+
+        let devices: Vec<Reference> = targets.into_iter().map(|(device, _)| device).collect();
+        outcomes.extend(self.wait(&task.id, &devices, &options, progress).await?);
+        Ok(outcomes)
+    }
+
+    /// Polls a deployment task until it finishes or `options.timeout` passes,
+    /// with one last poll at the deadline.
+    pub async fn wait<F>(
+        &self,
+        task_id: &str,
+        devices: &[Reference],
+        options: &DeployOptions,
+        mut progress: F,
+    ) -> Result<Vec<DeployOutcome>, FmcError>
+    where
+        F: FnMut(&TaskStatus),
+    {
+        let deadline = Instant::now() + options.timeout;
+        loop {
+            let status = self.task_status(task_id).await?;
+            progress(&status);
+
+            let now = Instant::now();
+            if status.status.is_finished() || now >= deadline {
+                return Ok(devices
+                    .iter()
+                    .map(|device| DeployOutcome::from_task(device, &status))
+                    .collect());
+            }
+            delay_for(options.poll_interval.min(deadline - now)).await;
+        }
+    }
+}
+
+/// Splits deployable devices into the ones to deploy (with their version)
+/// and NotDeployable outcomes for requested IDs that can't be.
+fn select_targets(
+    deployable: Vec<DeployableDevice>,
+    wanted: Option<&[String]>,
+) -> (Vec<(Reference, Option<String>)>, Vec<DeployOutcome>) {
+    let targets: Vec<(Reference, Option<String>)> = deployable
+        .into_iter()
+        .filter(|item| item.can_be_deployed != Some(false))
+        .filter_map(|item| {
+            let version = item.version;
+            item.device.map(|device| (device, version))
+        })
+        .filter(|(device, _)| match wanted {
+            Some(wanted) => wanted.contains(&device.id),
+            None => true,
+        })
+        .collect();
+
+    let missing = wanted
+        .unwrap_or(&[])
+        .iter()
+        .filter(|id| !targets.iter().any(|(device, _)| &device.id == *id))
+        .map(|id| DeployOutcome {
+            device_id: id.clone(),
+            device_name: None,
+            result: DeployResult::NotDeployable,
+        })
+        .collect();
+
+    (targets, missing)
+}
+
+/// Versions are epoch milliseconds; a request must carry the newest.
+fn newest_version(targets: &[(Reference, Option<String>)]) -> String {
+    targets
+        .iter()
+        .filter_map(|(_, version)| version.as_ref())
+        .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .cloned()
+        .unwrap_or_default()
+}
+
+impl DeploymentRequest {
+    /// Deploys `version` to the given device IDs, skipping warnings.
+    pub fn new(version: &str, device_ids: Vec<&str>) -> DeploymentRequest {
+        DeploymentRequest {
+            _type: "DeploymentRequest".to_string(),
+            version: version.to_string(),
+            force_deploy: false,
+            ignore_warning: true,
+            device_list: device_ids.into_iter().map(String::from).collect(),
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// Redeploys even if FMC thinks the device is up to date.
+    pub fn force_deploy(mut self, force_deploy: bool) -> Self {
+        self.force_deploy = force_deploy;
+        self
+    }
+
+    pub fn ignore_warning(mut self, ignore_warning: bool) -> Self {
+        self.ignore_warning = ignore_warning;
+        self
+    }
+}
+
+impl DeployOptions {
+    /// Every deployable device, polled every 10 seconds for up to 30 minutes.
+    pub fn new() -> DeployOptions {
+        DeployOptions {
+            devices: None,
+            force_deploy: false,
+            ignore_warning: true,
+            poll_interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(30 * 60),
+        }
+    }
+
+    /// Deploys only these device IDs instead of everything pending.
+    pub fn devices(mut self, device_ids: &[&str]) -> Self {
+        self.devices = Some(device_ids.iter().map(|id| id.to_string()).collect());
+        self
+    }
+
+    pub fn force_deploy(mut self, force_deploy: bool) -> Self {
+        self.force_deploy = force_deploy;
+        self
+    }
+
+    pub fn ignore_warning(mut self, ignore_warning: bool) -> Self {
+        self.ignore_warning = ignore_warning;
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        DeployOptions::new()
+    }
+}
+
+impl DeployOutcome {
+    /// Reads one device's result from the last task status polled. The
+    /// device's own sub-task wins over the overall task state.
+    pub fn from_task(device: &Reference, status: &TaskStatus) -> DeployOutcome {
+        let sub_task = status
+            .sub_tasks
+            .iter()
+            .find(|sub_task| sub_task.target.as_ref().map(|t| &t.id) == Some(&device.id));
+
+        let result = match sub_task {
+            Some(SubTask {
+                status: state,
+                message,
+                ..
+            }) if state.is_finished() => DeployResult::from_state(state, message),
+            _ if status.status.is_finished() => {
+                DeployResult::from_state(&status.status, &status.message)
+            }
+            _ => DeployResult::TimedOut,
+        };
+
+        DeployOutcome {
+            device_id: device.id.clone(),
+            device_name: device.name.clone(),
+            result,
+        }
+    }
+}
+
+impl DeployResult {
+    fn from_state(state: &TaskState, message: &Option<String>) -> DeployResult {
+        match state {
+            TaskState::Failed => DeployResult::Failed(message.clone()),
+            _ => DeployResult::Deployed,
+        }
+    }
+}
+
+impl TaskState {
+    /// Deployed, Success or Failed.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            TaskState::Deployed | TaskState::Success | TaskState::Failed
+        )
+    }
+}
+
+impl From<String> for TaskState {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Pending" => TaskState::Pending,
+            "Running" => TaskState::Running,
+            "Deploying" => TaskState::Deploying,
+            "Deployed" => TaskState::Deployed,
+            "Success" => TaskState::Success,
+            "Failed" => TaskState::Failed,
+            _ => TaskState::Other(value),
+        }
+    }
+}
+
+impl From<TaskState> for String {
+    fn from(value: TaskState) -> Self {
+        match value {
+            TaskState::Pending => "Pending".to_string(),
+            TaskState::Running => "Running".to_string(),
+            TaskState::Deploying => "Deploying".to_string(),
+            TaskState::Deployed => "Deployed".to_string(),
+            TaskState::Success => "Success".to_string(),
+            TaskState::Failed => "Failed".to_string(),
+            TaskState::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::device_ref;
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fn_outcome_from_task() {
+        let status: TaskStatus = serde_json::from_str(
+            r#"{"id": "task-1", "status": "Deploying", "subTasks": [
+                {"target": {"id": "dev-1"}, "status": "Deployed"},
+                {"target": {"id": "dev-2"}, "status": "Failed", "message": "no route"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let results: Vec<DeployResult> = ["dev-1", "dev-2", "dev-3"]
+            .iter()
+            .map(|id| DeployOutcome::from_task(&device_ref(id), &status).result)
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                DeployResult::Deployed,
+                DeployResult::Failed(Some("no route".to_string())),
+                DeployResult::TimedOut,
+            ]
+        );
+
+        let status: TaskStatus =
+            serde_json::from_str(r#"{"id": "task-1", "status": "Failed", "message": "boom"}"#)
+                .unwrap();
+        assert_eq!(
+            DeployOutcome::from_task(&device_ref("dev-1"), &status).result,
+            DeployResult::Failed(Some("boom".to_string()))
+        );
+    }
+
+    #[test]
+    fn fn_select_targets() {
+        let deployable: Vec<DeployableDevice> = serde_json::from_str(
+            r#"[
+                {"version": "999", "device": {"id": "dev-1"}, "canBeDeployed": true},
+                {"version": "1457566762351", "device": {"id": "dev-2"}},
+                {"version": "1457566762999", "device": {"id": "dev-3"}, "canBeDeployed": false}
+            ]"#,
+        )
+        .unwrap();
+
+        let (targets, missing) = select_targets(deployable.clone(), None);
+        assert_eq!(targets.len(), 2);
+        assert!(missing.is_empty());
+        assert_eq!(newest_version(&targets), "1457566762351");
+
+        let wanted = vec!["dev-2".to_string(), "dev-3".to_string()];
+        let (targets, missing) = select_targets(deployable, Some(&wanted));
+        assert_eq!(targets[0].0.id, "dev-2");
+        assert_eq!(missing[0].device_id, "dev-3");
+        assert_eq!(missing[0].result, DeployResult::NotDeployable);
+    }
+
+    #[test]
+    fn fn_deployment_request() {
+        let request = DeploymentRequest::new("1457566762351", vec!["dev-1"]).force_deploy(true);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "type": "DeploymentRequest",
+                "version": "1457566762351",
+                "forceDeploy": true,
+                "ignoreWarning": true,
+                "deviceList": ["dev-1"]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_deploy_and_poll() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let deployments = client.deployments();
+        let branch_2 = "7a1e9c3e-5a4c-11ea-9b3c-8bd4e2c0f1a2";
+
+        let options = DeployOptions::new()
+            .devices(&[branch_2, "dev-x"])
+            .poll_interval(Duration::from_millis(10));
+        let mut seen = Vec::new();
+        let outcomes = deployments
+            .deploy(options, |status| seen.push(status.status.clone()))
+            .await?;
+        assert_eq!(seen, vec![TaskState::Deploying, TaskState::Deployed]);
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].device_id, "dev-x");
+        assert_eq!(outcomes[0].result, DeployResult::NotDeployable);
+        assert_eq!(outcomes[1].device_id, branch_2);
+        assert_eq!(outcomes[1].device_name.as_deref(), Some("ftd-branch-2"));
+        assert_eq!(outcomes[1].result, DeployResult::Deployed);
+        assert!(mock
+            .requests()
+            .iter()
+            .any(|r| r.starts_with("POST ") && r.ends_with("/deployment/deploymentrequests")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_wait_failed_and_timed_out() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        mock.fixture(
+            "taskstatuses",
+            vec![
+                json!({"id": "task-failed", "status": "Failed", "subTasks": [
+                    {"target": {"id": "dev-1"}, "status": "Failed", "message": "no route"}
+                ]}),
+                json!({"id": "task-stuck", "status": "Deploying"}),
+            ],
+        );
+        let client = mock.client().await;
+        let deployments = client.deployments();
+        let devices = [device_ref("dev-1")];
+
+        let options = DeployOptions::new().poll_interval(Duration::from_millis(40));
+        let outcomes = deployments
+            .wait("task-failed", &devices, &options, |_| {})
+            .await?;
+        assert_eq!(
+            outcomes[0].result,
+            DeployResult::Failed(Some("no route".to_string()))
+        );
+
+        // Polls until the deadline, and once more at it.
+        let options = options.timeout(Duration::from_millis(100));
+        let start = Instant::now();
+        let mut polls = Vec::new();
+        let outcomes = deployments
+            .wait("task-stuck", &devices, &options, |_| {
+                polls.push(Instant::now())
+            })
+            .await?;
+        assert_eq!(outcomes[0].result, DeployResult::TimedOut);
+        assert!(polls.len() >= 2);
+        assert!(*polls.last().unwrap() >= start + Duration::from_millis(100));
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use super::{core, Deserialize, Extra, FmcClient, Reference, Serialize};
+use std::time::Duration;
+
+// /deployment/deployabledevices
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeployableDevice {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Pending configuration version; pass the newest to a deployment request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<Reference>,
+    #[serde(rename = "canBeDeployed", skip_serializing_if = "Option::is_none")]
+    pub can_be_deployed: Option<bool>,
+    #[serde(rename = "upToDate", skip_serializing_if = "Option::is_none")]
+    pub up_to_date: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /deployment/deploymentrequests
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeploymentRequest {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub version: String,
+    #[serde(rename = "forceDeploy")]
+    pub force_deploy: bool,
+    #[serde(rename = "ignoreWarning")]
+    pub ignore_warning: bool,
+    #[serde(rename = "deviceList")]
+    pub device_list: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<DeploymentMetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeploymentMetaData {
+    pub task: Option<Task>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// The task FMC started for a deployment; poll it via taskstatuses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Task {
+    pub id: String,
+    #[serde(rename = "taskType", skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /taskstatuses/{id}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskStatus {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(rename = "taskType", skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+    pub status: TaskState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(rename = "subTasks", default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_tasks: Vec<SubTask>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Per-device progress inside a deployment task, when FMC reports it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubTask {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<Reference>,
+    pub status: TaskState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Task state as FMC spells it. Unknown states are kept in Other and
+/// treated as still running.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum TaskState {
+    Pending,
+    Running,
+    Deploying,
+    Deployed,
+    Success,
+    Failed,
+    Other(String),
+}
+
+/// How often to poll taskstatuses and when to give up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployOptions {
+    pub(crate) devices: Option<Vec<String>>,
+    pub(crate) force_deploy: bool,
+    pub(crate) ignore_warning: bool,
+    pub(crate) poll_interval: Duration,
+    pub(crate) timeout: Duration,
+}
+
+/// What happened to one device in a deployment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployOutcome {
+    pub device_id: String,
+    pub device_name: Option<String>,
+    pub result: DeployResult,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployResult {
+    Deployed,
+    /// FMC's message, if it gave one.
+    Failed(Option<String>),
+    /// Still running when the timeout expired.
+    TimedOut,
+    /// Requested but not in the deployable list, e.g. already up to date.
+    NotDeployable,
+}
+
+pub struct Deployments<'c> {
+    pub(crate) client: &'c FmcClient,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // GET /api/fmc_config/v1/domain/{domainUUID}/deployment/deployabledevices?expanded=true
+    #[test]
+    fn deployable_device_test() {
+        let raw_str = r#"{
+            "version": "1457566762351",
+            "name": "ftd-branch-1",
+            "type": "DeployableDevice",
+            "device": {"id": "dev-1", "type": "Device", "name": "ftd-branch-1"},
+            "canBeDeployed": true,
+            "upToDate": false,
+            "policyStatusList": []
+        }"#;
+
+        let parsed: DeployableDevice = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.version.as_deref(), Some("1457566762351"));
+        assert_eq!(parsed.device.as_ref().unwrap().id, "dev-1");
+        assert!(parsed.extra.contains_key("policyStatusList"));
+    }
+
+    // GET /api/fmc_config/v1/domain/{domainUUID}/taskstatuses/{objectId}
+    #[test]
+    fn task_status_test() {
+        let raw_str = r#"{
+            "id": "task-1",
+            "type": "TaskStatus",
+            "taskType": "DEVICE_DEPLOYMENT",
+            "status": "Deploying",
+            "message": "Deploying 2 devices",
+            "subTasks": [
+                {"target": {"id": "dev-1", "type": "Device"}, "status": "Deployed"},
+                {"target": {"id": "dev-2", "type": "Device"}, "status": "Failed",
+                 "message": "Interface Gi0/1 not found"}
+            ]
+        }"#;
+
+        let parsed: TaskStatus = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.status, TaskState::Deploying);
+        assert_eq!(parsed.sub_tasks[0].status, TaskState::Deployed);
+        assert_eq!(parsed.sub_tasks[1].status, TaskState::Failed);
+
+        let parsed: TaskStatus =
+            serde_json::from_str(r#"{"id": "task-1", "status": "Queued"}"#).unwrap();
+        assert_eq!(parsed.status, TaskState::Other("Queued".to_string()));
+        assert!(parsed.sub_tasks.is_empty());
+    }
+}
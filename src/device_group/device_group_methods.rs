@@ -0,0 +1,306 @@
+use super::device_group_schema::{DeviceGroup, DeviceGroups, MembershipChange};
+use super::DEVICE_GROUP_TYPE;
+use super::{Extra, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator, Reference};
+use super::{Filter, StatusCode, Uuid};
+
+impl FmcClient {
+    pub fn device_groups(&self) -> DeviceGroups<'_> {
+        DeviceGroups { client: self }
+    }
+}
+
+impl<'c> DeviceGroups<'c> {
+    /// Every device group, expanded so each lists its members.
+    pub fn list(&self) -> Paginator<'c, ObjectList<DeviceGroup>> {
+        self.client.paginate(FmcApi::DeviceGroups).expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<DeviceGroup, FmcError> {
+        self.client
+            .get_json(FmcApi::DeviceGroup(id.to_string()))
+            .await
+    }
+
+    /// The group whose name matches exactly, if there is one.
+    pub async fn find(&self, name: &str) -> Result<Option<DeviceGroup>, FmcError> {
+        let groups = self.list().collect_all().await?;
+        Ok(groups.into_iter().find(|group| group.name == name))
+    }
+
+    pub async fn create(&self, group: &DeviceGroup) -> Result<DeviceGroup, FmcError> {
+        self.client
+            .post_json(FmcApi::DeviceGroups, FmcQuery::new(), group)
+            .await
+    }
+
+    /// Replaces the group with the same ID, members included.
+    pub async fn update(&self, group: &DeviceGroup) -> Result<DeviceGroup, FmcError> {
+        let id = match &group.id {
+            Some(id) => id.clone(),
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client.put_json(FmcApi::DeviceGroup(id), group).await
+    }
+
+    /// Deletes the group. Its devices stay on FMC, ungrouped.
+    pub async fn delete(&self, id: &str) -> Result<DeviceGroup, FmcError> {
+        self.client
+            .delete_json(FmcApi::DeviceGroup(id.to_string()))
+            .await
+    }
+
+    /// Adds devices, each given by name or ID, to the group. Sends one PUT
+    /// if any of them are new to it and none otherwise; returns what changed.
+    pub async fn add_devices(
+        &self,
+        id: &str,
+        devices: &[&str],
+    ) -> Result<MembershipChange, FmcError> {
+        let devices = self.resolve(devices).await?;
+        let group = self.get(id).await?;
+        let mut wanted = group.members.clone();
+        wanted.extend(devices);
+        self.reconcile(group, wanted).await
+    }
+
+    /// Removes devices, each given by name or ID, from the group. Devices
+    /// not in it are ignored.
+    pub async fn remove_devices(
+        &self,
+        id: &str,
+        devices: &[&str],
+    ) -> Result<MembershipChange, FmcError> {
+        let devices = self.resolve(devices).await?;
+        let group = self.get(id).await?;
+        let wanted = group
+            .members
+            .iter()
+            .filter(|member| !devices.iter().any(|device| device.id == member.id))
+            .cloned()
+            .collect();
+        self.reconcile(group, wanted).await
+    }
+
+    /// Makes `devices`, each given by name or ID, the group's only members.
+    pub async fn set_devices(
+        &self,
+        id: &str,
+        devices: &[&str],
+    ) -> Result<MembershipChange, FmcError> {
+        let devices = self.resolve(devices).await?;
+        let group = self.get(id).await?;
+        self.reconcile(group, devices).await
+    }
+
+    /// PUTs `wanted` as the members unless it already matches them.
+    async fn reconcile(
+        &self,
+        mut group: DeviceGroup,
+        wanted: Vec<Reference>,
+    ) -> Result<MembershipChange, FmcError> {
+        let change = group.diff(&wanted);
+        if !change.is_empty() {
+            group.apply(&change);
+            self.update(&group).await?;
+        }
+        Ok(change)
+    }
+
+    /// Device references for names or IDs, in the order given. Fails with
+    /// NotFound on the first that matches no device.
+    async fn resolve(&self, devices: &[&str]) -> Result<Vec<Reference>, FmcError> {
+        let mut found = Vec::with_capacity(devices.len());
+        for wanted in devices {
+            found.push(self.resolve_one(wanted).await?);
+        }
+        Ok(found)
+    }
+
+    /// GETs an ID directly and looks a name up with a name filter, so the
+    /// device inventory is never listed.
+    async fn resolve_one(&self, wanted: &str) -> Result<Reference, FmcError> {
+        let not_found = || FmcError::NotFound(wanted.to_string());
+        if Uuid::parse_str(wanted).is_ok() {
+            return match self.client.devices().get(wanted).await {
+                Ok(device) => Ok(Reference {
+                    id: device.id,
+                    _type: Some(device._type),
+                    name: Some(device.name),
+                }),
+                Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Err(not_found()),
+                Err(e) => Err(e),
+            };
+        }
+
+        let named = self
+            .client
+            .devices()
+            .paginate()
+            .filter(Filter::Name(wanted.to_string()))
+            .collect_all()
+            .await?;
+        named
+            .into_iter()
+            .find(|device| device.name == wanted)
+            .map(|device| Reference {
+                id: device.id,
+                _type: Some(device._type),
+                name: Some(device.name),
+            })
+            .ok_or_else(not_found)
+    }
+}
+
+impl DeviceGroup {
+    /// An empty group, ready for create().
+    pub fn new(name: &str) -> DeviceGroup {
+        DeviceGroup {
+            id: None,
+            _type: DEVICE_GROUP_TYPE.to_string(),
+            name: name.to_string(),
+            description: None,
+            members: Vec::new(),
+            links: None,
+            extra: Extra::new(),
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> DeviceGroup {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Adds a device, HA pair or cluster.
+    pub fn member(mut self, member: Reference) -> DeviceGroup {
+        self.members.push(member);
+        self
+    }
+
+    /// What it takes to go from the current members to `wanted`, compared
+    /// by ID. Duplicates in `wanted` count once.
+    pub fn diff(&self, wanted: &[Reference]) -> MembershipChange {
+        let mut added: Vec<Reference> = Vec::new();
+        for member in wanted {
+            let present = |list: &[Reference]| list.iter().any(|m| m.id == member.id);
+            if !present(&self.members) && !present(&added) {
+                added.push(member.clone());
+            }
+        }
+        let removed = self
+            .members
+            .iter()
+            .filter(|member| !wanted.iter().any(|m| m.id == member.id))
+            .cloned()
+            .collect();
+        MembershipChange { added, removed }
+    }
+
+    /// Applies a change from diff(): drops removed members, then appends
+    /// added ones not already present.
+    pub fn apply(&mut self, change: &MembershipChange) {
+        self.members
+            .retain(|member| !change.removed.iter().any(|m| m.id == member.id));
+        for member in &change.added {
+            if !self.members.iter().any(|m| m.id == member.id) {
+                self.members.push(member.clone());
+            }
+        }
+    }
+}
+
+impl MembershipChange {
+    /// Nothing to add or remove, so no PUT is needed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::device_ref;
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+
+    #[test]
+    fn fn_membership_diff() {
+        let mut group = DeviceGroup::new("branches")
+            .member(device_ref("dev-1"))
+            .member(device_ref("dev-2"));
+
+        let change = group.diff(&[
+            device_ref("dev-2"),
+            device_ref("dev-3"),
+            device_ref("dev-3"),
+        ]);
+        assert_eq!(change.added, vec![device_ref("dev-3")]);
+        assert_eq!(change.removed, vec![device_ref("dev-1")]);
+
+        group.apply(&change);
+        let ids: Vec<&str> = group.members.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["dev-2", "dev-3"]);
+        assert!(group
+            .diff(&[device_ref("dev-3"), device_ref("dev-2")])
+            .is_empty());
+
+        let body = serde_json::to_value(&group).unwrap();
+        assert_eq!(body["type"], "DeviceGroup");
+        assert!(body.get("id").is_none());
+        assert_eq!(body["members"][1]["id"], "dev-3");
+    }
+
+    #[tokio::test]
+    async fn fn_place_devices() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let groups = client.device_groups();
+
+        let group = groups.create(&DeviceGroup::new("branches")).await?;
+        let id = group.id.unwrap();
+        assert_eq!(groups.find("branches").await?.unwrap().id.unwrap(), id);
+
+        let branch_2 = "7a1e9c3e-5a4c-11ea-9b3c-8bd4e2c0f1a2";
+        let change = groups.add_devices(&id, &["ftd-branch-1", branch_2]).await?;
+        assert_eq!(change.added.len(), 2);
+        assert_eq!(change.added[1].name.as_deref(), Some("ftd-branch-2"));
+        assert_eq!(groups.get(&id).await?.members.len(), 2);
+
+        let puts = || {
+            mock.requests()
+                .iter()
+                .filter(|r| r.starts_with("PUT "))
+                .count()
+        };
+        assert_eq!(puts(), 1);
+        assert!(groups.add_devices(&id, &["ftd-branch-2"]).await?.is_empty());
+        assert_eq!(puts(), 1);
+
+        let change = groups
+            .set_devices(&id, &["ftd-branch-2", "ftd-dc-1"])
+            .await?;
+        assert_eq!(change.added.len(), 1);
+        assert_eq!(change.removed[0].name.as_deref(), Some("ftd-branch-1"));
+        let change = groups.remove_devices(&id, &["ftd-dc-1"]).await?;
+        assert_eq!(change.removed.len(), 1);
+        assert_eq!(groups.get(&id).await?.members[0].id, branch_2);
+        assert_eq!(puts(), 3);
+
+        assert!(matches!(
+            groups.add_devices(&id, &["no-such-ftd"]).await,
+            Err(FmcError::NotFound(name)) if name == "no-such-ftd"
+        ));
+        let missing = "00000000-0000-0000-0000-00000000dead";
+        assert!(matches!(
+            groups.add_devices(&id, &[missing]).await,
+            Err(FmcError::NotFound(name)) if name == missing
+        ));
+        // Names go through the filter; the inventory is never listed whole.
+        assert!(mock
+            .requests()
+            .iter()
+            .filter(|r| r.contains("/devicerecords?"))
+            .all(|r| r.contains("filter=name")));
+        groups.delete(&id).await?;
+        assert!(groups.find("branches").await?.is_none());
+        Ok(())
+    }
+}
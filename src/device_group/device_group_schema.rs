@@ -0,0 +1,34 @@
+use super::{core, Deserialize, Extra, FmcClient, Reference, Serialize};
+
+// /devicegroups/devicegrouprecords
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Devices, HA pairs and clusters in the group. A device belongs to
+    /// at most one group.
+    #[serde(default)]
+    pub members: Vec<Reference>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// What a membership update adds to and removes from a group, by ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MembershipChange {
+    pub added: Vec<Reference>,
+    pub removed: Vec<Reference>,
+}
+
+/// Device groups, from client.device_groups().
+#[derive(Debug)]
+pub struct DeviceGroups<'c> {
+    pub(super) client: &'c FmcClient,
+}
@@ -0,0 +1,14 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra, Reference};
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::{Filter, FmcApi, FmcQuery};
+use super::Uuid;
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+mod device_group_methods;
+pub mod device_group_schema;
+
+/// The "type" FMC gives device groups.
+const DEVICE_GROUP_TYPE: &str = "DeviceGroup";
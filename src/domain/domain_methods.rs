@@ -0,0 +1,189 @@
+use super::domain_schema::{DomainInfo, Domains};
+use super::DOMAIN_SEPARATOR;
+use super::{FmcApi, FmcClient, FmcError, ObjectList, Paginator, Uuid};
+
+impl FmcClient {
+    pub fn domains(&self) -> Domains<'_> {
+        Domains { client: self }
+    }
+}
+
+impl<'c> Domains<'c> {
+    /// Domains the API user can access, from the DOMAINS login header.
+    /// Logs in first if needed.
+    pub async fn accessible(&self) -> Result<Vec<DomainInfo>, FmcError> {
+        self.client.login().await?;
+        Ok(self.client.session().await.domains().to_vec())
+    }
+
+    /// Every domain on the FMC, parents before their subdomains.
+    pub fn hierarchy(&self) -> Paginator<'c, ObjectList<DomainInfo>> {
+        self.client.paginate(FmcApi::Domains)
+    }
+
+    /// Direct subdomains of `parent` (a full name such as "Global").
+    pub async fn children(&self, parent: &str) -> Result<Vec<DomainInfo>, FmcError> {
+        let domains = self.hierarchy().collect_all().await?;
+        Ok(domains
+            .into_iter()
+            .filter(|domain| domain.parent() == Some(parent))
+            .collect())
+    }
+
+    /// Domains without subdomains, which is where devices live.
+    pub async fn leaves(&self) -> Result<Vec<DomainInfo>, FmcError> {
+        let domains = self.hierarchy().collect_all().await?;
+        Ok(domains
+            .iter()
+            .filter(|domain| !domains.iter().any(|d| d.parent() == Some(&domain.name)))
+            .cloned()
+            .collect())
+    }
+}
+
+impl DomainInfo {
+    pub fn domain_uuid(&self) -> Result<Uuid, FmcError> {
+        Ok(Uuid::parse_str(&self.uuid)?)
+    }
+
+    /// Full name of the parent domain; None for Global.
+    pub fn parent(&self) -> Option<&str> {
+        self.name
+            .rsplit_once(DOMAIN_SEPARATOR)
+            .map(|(parent, _)| parent)
+    }
+
+    /// Last part of the name, e.g. "Tenant-A" for "Global/Tenant-A".
+    pub fn short_name(&self) -> &str {
+        self.name
+            .rsplit(DOMAIN_SEPARATOR)
+            .next()
+            .unwrap_or(&self.name)
+    }
+}
+
+/// Finds a domain by UUID, full name, or short name when only one domain
+/// has it.
+pub(crate) fn resolve(domains: &[DomainInfo], domain: &str) -> Result<Uuid, FmcError> {
+    let exact = domains
+        .iter()
+        .find(|d| d.name == domain || d.uuid.eq_ignore_ascii_case(domain));
+    if let Some(found) = exact {
+        return found.domain_uuid();
+    }
+
+    let mut short = domains.iter().filter(|d| d.short_name() == domain);
+    match (short.next(), short.next()) {
+        (Some(found), None) => found.domain_uuid(),
+        _ => Err(FmcError::UnknownDomain(domain.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::super::super::mock::{MOCK_DOMAIN_UUID, MOCK_SUBDOMAINS};
+    use super::super::super::uri::uri_schema::Object;
+    use super::super::Extra;
+    use super::*;
+    use serde_json::json;
+
+    fn domain(name: &str, uuid: &str) -> DomainInfo {
+        DomainInfo {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            _type: Some("Domain".to_string()),
+            extra: Extra::new(),
+        }
+    }
+
+    #[test]
+    fn fn_resolve() {
+        let domains = vec![
+            domain("Global", "e276abec-e0f2-11e3-8169-6d9ed49b625f"),
+            domain("Global/Tenant-A", "0a0b0c0d-0000-4000-8000-00000000000a"),
+            domain(
+                "Global/Tenant-A/Lab",
+                "0a0b0c0d-0000-4000-8000-0000000000a1",
+            ),
+            domain(
+                "Global/Tenant-B/Lab",
+                "0a0b0c0d-0000-4000-8000-0000000000b1",
+            ),
+        ];
+        let uuid = |s| Uuid::parse_str(s).unwrap();
+
+        assert_eq!(
+            resolve(&domains, "Global/Tenant-A").unwrap(),
+            uuid("0a0b0c0d-0000-4000-8000-00000000000a")
+        );
+        assert_eq!(
+            resolve(&domains, "Tenant-A").unwrap(),
+            uuid("0a0b0c0d-0000-4000-8000-00000000000a")
+        );
+        assert_eq!(
+            resolve(&domains, "0A0B0C0D-0000-4000-8000-0000000000B1").unwrap(),
+            uuid("0a0b0c0d-0000-4000-8000-0000000000b1")
+        );
+        // Two domains are called "Lab".
+        assert!(matches!(
+            resolve(&domains, "Lab"),
+            Err(FmcError::UnknownDomain(_))
+        ));
+
+        assert_eq!(domains[0].parent(), None);
+        assert_eq!(domains[2].parent(), Some("Global/Tenant-A"));
+        assert_eq!(domains[2].short_name(), "Lab");
+    }
+
+    #[tokio::test]
+    async fn fn_domains() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let (tenant_a, tenant_a_uuid) = MOCK_SUBDOMAINS[0];
+
+        let accessible = client.domains().accessible().await?;
+        assert_eq!(accessible.len(), 3);
+        assert_eq!(accessible[0].uuid, MOCK_DOMAIN_UUID);
+
+        let leaves = client.domains().leaves().await?;
+        assert_eq!(leaves.len(), 2);
+        assert_eq!(leaves[0].name, tenant_a);
+        assert_eq!(client.domains().children("Global").await?, leaves);
+
+        mock.fixture_in(
+            tenant_a_uuid,
+            "object/hosts",
+            vec![json!({"id": "a-1", "type": "Host", "name": "tenant-web", "value": "10.9.0.1"})],
+        );
+        let hosts = client.objects().hosts();
+        assert_eq!(hosts.list().expanded(true).collect_all().await?.len(), 3);
+
+        // One request into Tenant-A, the rest stay in Global.
+        let resp = client
+            .request_in("Tenant-A")
+            .await?
+            .get(FmcApi::Objects(Object::Hosts(None)))
+            .await?
+            .build()
+            .await?
+            .send()
+            .await?;
+        assert!(resp.status().is_success());
+        assert!(mock
+            .requests()
+            .last()
+            .unwrap()
+            .contains(&format!("/domain/{}/object/hosts", tenant_a_uuid)));
+        assert_eq!(hosts.list().expanded(true).collect_all().await?.len(), 3);
+
+        client.use_domain(tenant_a).await?;
+        let items = hosts.list().expanded(true).collect_all().await?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "tenant-web");
+
+        let err = client.use_domain("Tenant-C").await;
+        assert!(matches!(err, Err(FmcError::UnknownDomain(_))));
+        Ok(())
+    }
+}
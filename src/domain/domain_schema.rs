@@ -0,0 +1,19 @@
+use super::{Deserialize, Extra, FmcClient, Serialize};
+
+/// A domain as listed in the DOMAINS login header and by /info/domain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DomainInfo {
+    pub uuid: String,
+    /// Full path from Global, e.g. "Global/Tenant-A".
+    pub name: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Domain discovery: /api/fmc_platform/v1/info/domain
+#[derive(Debug)]
+pub struct Domains<'c> {
+    pub(super) client: &'c FmcClient,
+}
@@ -0,0 +1,15 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::Extra;
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::FmcApi;
+use super::Uuid;
+use serde::{Deserialize, Serialize};
+
+mod domain_methods;
+pub mod domain_schema;
+
+pub(crate) use domain_methods::resolve;
+
+/// Subdomains are named by their path from Global, e.g. "Global/Tenant-A".
+const DOMAIN_SEPARATOR: char = '/';
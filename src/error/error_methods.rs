@@ -0,0 +1,145 @@
+use std::fmt;
+
+use super::error_schema::{FmcError, FmcErrorBody};
+use super::StatusCode;
+
+impl FmcError {
+    /// Builds a Status error from a failed response, keeping FMC's
+    /// error description if the body has one.
+    pub(crate) fn from_status(status: StatusCode, body: &[u8]) -> FmcError {
+        FmcError::Status {
+            status,
+            body: serde_json::from_slice(body).ok(),
+        }
+    }
+
+    /// The HTTP status FMC replied with, if this is a Status error.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            FmcError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl FmcErrorBody {
+    /// All message descriptions joined into one line.
+    pub fn description(&self) -> String {
+        self.error
+            .messages
+            .iter()
+            .filter_map(|m| m.description.as_deref())
+            .collect::<Vec<&str>>()
+            .join("; ")
+    }
+}
+
+impl fmt::Display for FmcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FmcError::Transport(e) => write!(f, "transport error: {}", e),
+            FmcError::Tls(e) => write!(f, "TLS error: {}", e),
+            FmcError::Status {
+                status,
+                body: Some(body),
+            } => write!(f, "FMC returned {}: {}", status, body.description()),
+            FmcError::Status { status, body: None } => write!(f, "FMC returned {}", status),
+            FmcError::MissingHeader(name) => write!(f, "response is missing the {} header", name),
+            FmcError::BadHeader(name) => write!(f, "could not parse the {} header", name),
+            FmcError::BadDomainUuid(e) => write!(f, "invalid domain UUID: {}", e),
+            FmcError::Json(e) => write!(f, "could not decode JSON at {}: {}", e.path(), e.inner()),
+            FmcError::Encode(e) => write!(f, "could not encode request body: {}", e),
+            FmcError::Request(e) => write!(f, "could not build request: {}", e),
+            FmcError::MissingField(name) => write!(f, "request is missing {}", name),
+            FmcError::InvalidId(id) => write!(f, "invalid object ID {:?}", id),
+            FmcError::NotFound(name) => write!(f, "no object named {:?}", name),
+            FmcError::InvalidPort(port) => write!(f, "invalid port or port range {:?}", port),
+            FmcError::UnsupportedFilter(filter) => {
+                write!(f, "filter {} is not supported by this endpoint", filter)
+            }
+            FmcError::InvalidCertificate(problem) => write!(f, "invalid certificate: {}", problem),
+            FmcError::InvalidFingerprint(pin) => {
+                write!(f, "invalid SHA-256 certificate fingerprint {:?}", pin)
+            }
+            FmcError::CertificateMismatch(seen) => {
+                write!(f, "certificate {} matches no pinned fingerprint", seen)
+            }
+            FmcError::UnknownDomain(domain) => write!(f, "no accessible domain {:?}", domain),
+            FmcError::InvalidVersion(version) => write!(f, "invalid version {:?}", version),
+            FmcError::UnsupportedVersion {
+                endpoint,
+                required,
+                found,
+            } => write!(
+                f,
+                "{} needs FMC {} or later, this FMC runs {}",
+                endpoint, required, found
+            ),
+            FmcError::Taxii(problem) => write!(f, "unexpected TAXII reply: {}", problem),
+        }
+    }
+}
+
+impl std::error::Error for FmcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FmcError::Transport(e) => Some(e),
+            FmcError::Tls(e) => Some(e),
+            FmcError::BadDomainUuid(e) => Some(e),
+            FmcError::Json(e) => Some(e.inner()),
+            FmcError::Encode(e) => Some(e),
+            FmcError::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<hyper::Error> for FmcError {
+    fn from(e: hyper::Error) -> Self {
+        FmcError::Transport(e)
+    }
+}
+
+impl From<native_tls::Error> for FmcError {
+    fn from(e: native_tls::Error) -> Self {
+        FmcError::Tls(e)
+    }
+}
+
+impl From<uuid::Error> for FmcError {
+    fn from(e: uuid::Error) -> Self {
+        FmcError::BadDomainUuid(e)
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for FmcError {
+    fn from(e: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        FmcError::Json(e)
+    }
+}
+
+impl From<http::Error> for FmcError {
+    fn from(e: http::Error) -> Self {
+        FmcError::Request(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_from_status() {
+        let body = br#"{"error":{"category":"FRAMEWORK","messages":[{"description":"Invalid token"}],"severity":"ERROR"}}"#;
+        let err = FmcError::from_status(StatusCode::UNAUTHORIZED, body);
+
+        assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            err.to_string(),
+            "FMC returned 401 Unauthorized: Invalid token"
+        );
+
+        let err = FmcError::from_status(StatusCode::BAD_GATEWAY, b"<html>nope</html>");
+        assert_eq!(err.to_string(), "FMC returned 502 Bad Gateway");
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Deserialize, StatusCode};
+
+/// Everything that can go wrong while talking to an FMC.
+#[derive(Debug)]
+pub enum FmcError {
+    /// The connection to the FMC failed or was dropped.
+    Transport(hyper::Error),
+    /// The TLS connector could not be set up.
+    Tls(native_tls::Error),
+    /// FMC answered with a non-2xx status. The body is kept when it
+    /// parses as FMC's standard error JSON.
+    Status {
+        status: StatusCode,
+        body: Option<FmcErrorBody>,
+    },
+    /// A header FMC should always send (e.g. X-auth-access-token) was absent.
+    MissingHeader(&'static str),
+    /// A header was present but its value could not be parsed.
+    BadHeader(&'static str),
+    /// The DOMAIN_UUID header was not a valid UUID.
+    BadDomainUuid(uuid::Error),
+    /// A response body did not match the expected schema.
+    /// The error carries the JSON path of the offending field.
+    Json(serde_path_to_error::Error<serde_json::Error>),
+    /// A request body could not be serialized to JSON.
+    Encode(serde_json::Error),
+    /// The request could not be assembled (bad URI, header value, ...).
+    Request(http::Error),
+    /// A builder step that the request depends on was skipped.
+    MissingField(&'static str),
+    /// An object or container ID is empty or contains characters
+    /// that would change the URL path.
+    InvalidId(String),
+    /// Nothing on the FMC has this name.
+    NotFound(String),
+    /// A port or port range is out of order or not a number.
+    InvalidPort(String),
+    /// The endpoint does not accept this filter clause.
+    UnsupportedFilter(String),
+    /// A certificate given to TlsConfig could not be used.
+    InvalidCertificate(String),
+    /// A certificate pin is not a SHA-256 fingerprint in hex.
+    InvalidFingerprint(String),
+    /// The FMC's certificate matched none of the pinned fingerprints.
+    /// Carries the fingerprint it presented.
+    CertificateMismatch(String),
+    /// No domain the user can access has this name or UUID.
+    UnknownDomain(String),
+    /// A version string had no numbers in it.
+    InvalidVersion(String),
+    /// The endpoint (an FmcApi variant) was added in a later FMC release
+    /// than this one runs.
+    UnsupportedVersion {
+        endpoint: String,
+        required: String,
+        found: String,
+    },
+    /// A TAXII server's reply was missing something the protocol requires.
+    Taxii(String),
+}
+
+/// FMC's JSON error reply, e.g.
+/// {"error":{"category":"FRAMEWORK","messages":[{"description":"..."}],"severity":"ERROR"}}
+#[derive(Deserialize, Debug)]
+pub struct FmcErrorBody {
+    pub error: FmcErrorDetail,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FmcErrorDetail {
+    pub category: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<FmcErrorMessage>,
+    pub severity: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FmcErrorMessage {
+    pub description: Option<String>,
+}
@@ -0,0 +1,5 @@
+use http::StatusCode;
+use serde::Deserialize;
+
+mod error_methods;
+pub mod error_schema;
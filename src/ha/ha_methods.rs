@@ -0,0 +1,418 @@
+use super::ha_schema::{FailoverLink, FailoverStatus, HaAction, HaActionRequest, HaBootstrap};
+use super::ha_schema::{HaPair, HaPairs, MonitoredInterface, PeerState};
+use super::HA_PAIR_TYPE;
+use super::{Extra, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator, Reference};
+use std::fmt;
+use std::time::Duration;
+use tokio::time::{delay_for, Instant};
+
+impl FmcClient {
+    pub fn ha_pairs(&self) -> HaPairs<'_> {
+        HaPairs { client: self }
+    }
+}
+
+impl<'c> HaPairs<'c> {
+    /// Every HA pair, expanded so each carries its failover status.
+    pub fn list(&self) -> Paginator<'c, ObjectList<HaPair>> {
+        self.client.paginate(FmcApi::DeviceHAPairs).expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<HaPair, FmcError> {
+        self.client
+            .get_json(FmcApi::DeviceHAPair(id.to_string()))
+            .await
+    }
+
+    /// Pairs the two devices. FMC does the work in a task; the reply's
+    /// metadata.task identifies it.
+    pub async fn create(&self, pair: &HaPair) -> Result<HaPair, FmcError> {
+        self.client
+            .post_json(FmcApi::DeviceHAPairs, FmcQuery::new(), pair)
+            .await
+    }
+
+    /// Splits the pair into standalone devices, keeping the active unit's
+    /// configuration. `force` breaks it even if the peers can't talk.
+    pub async fn break_pair(&self, id: &str, force: bool) -> Result<HaPair, FmcError> {
+        self.action(id, HaAction::Break, Some(force)).await
+    }
+
+    /// Makes the standby unit active.
+    pub async fn switch_active(&self, id: &str) -> Result<HaPair, FmcError> {
+        self.action(id, HaAction::Switch, None).await
+    }
+
+    /// Removes the pair from FMC along with both devices.
+    pub async fn delete(&self, id: &str) -> Result<HaPair, FmcError> {
+        self.client
+            .delete_json(FmcApi::DeviceHAPair(id.to_string()))
+            .await
+    }
+
+    pub async fn failover_status(&self, id: &str) -> Result<FailoverStatus, FmcError> {
+        Ok(FailoverStatus::from_pair(&self.get(id).await?))
+    }
+
+    /// Polls failover_status() every `poll_interval` until one unit is
+    /// active and the other standby, or `timeout` passes, polling once more
+    /// at the deadline. Returns the last status either way.
+    pub async fn wait_healthy(
+        &self,
+        id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<FailoverStatus, FmcError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self.failover_status(id).await?;
+            let now = Instant::now();
+            if status.is_healthy() || now >= deadline {
+                return Ok(status);
+            }
+            delay_for(poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Data interfaces of the pair and whether each is monitored.
+    pub fn monitored_interfaces(&self, id: &str) -> Paginator<'c, ObjectList<MonitoredInterface>> {
+        self.client
+            .paginate(FmcApi::HAMonitoredInterfaces(id.to_string(), None))
+            .expanded(true)
+    }
+
+    /// Saves monitoring and standby addresses for one interface.
+    pub async fn update_monitored_interface(
+        &self,
+        id: &str,
+        interface: &MonitoredInterface,
+    ) -> Result<MonitoredInterface, FmcError> {
+        let api = FmcApi::HAMonitoredInterfaces(id.to_string(), Some(interface.id.clone()));
+        self.client.put_json(api, interface).await
+    }
+
+    async fn action(
+        &self,
+        id: &str,
+        action: HaAction,
+        force_break: Option<bool>,
+    ) -> Result<HaPair, FmcError> {
+        let request = HaActionRequest {
+            id: id.to_string(),
+            _type: HA_PAIR_TYPE.to_string(),
+            action,
+            force_break,
+        };
+        self.client
+            .put_json(FmcApi::DeviceHAPair(id.to_string()), &request)
+            .await
+    }
+}
+
+impl HaPair {
+    /// A pair of the two devices using one link for both failover and
+    /// state.
+    pub fn new(
+        name: &str,
+        primary: Reference,
+        secondary: Reference,
+        failover_link: FailoverLink,
+    ) -> HaPair {
+        HaPair {
+            id: None,
+            _type: HA_PAIR_TYPE.to_string(),
+            name: name.to_string(),
+            primary,
+            secondary,
+            bootstrap: Some(HaBootstrap {
+                encryption: false,
+                key_scheme: None,
+                shared_key: None,
+                same_link: true,
+                lan_failover: failover_link,
+                stateful_failover: None,
+                extra: Extra::new(),
+            }),
+            metadata: None,
+            links: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// Carries connection state over its own link.
+    pub fn state_link(mut self, link: FailoverLink) -> HaPair {
+        if let Some(bootstrap) = &mut self.bootstrap {
+            bootstrap.same_link = false;
+            bootstrap.stateful_failover = Some(link);
+        }
+        self
+    }
+
+    /// Encrypts failover traffic with a shared key.
+    pub fn shared_key(mut self, key: &str) -> HaPair {
+        if let Some(bootstrap) = &mut self.bootstrap {
+            bootstrap.encryption = true;
+            bootstrap.key_scheme = Some("CUSTOM".to_string());
+            bootstrap.shared_key = Some(key.to_string());
+        }
+        self
+    }
+
+    /// The task FMC started for this create, break or switch.
+    pub fn task_id(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.task.as_ref())
+            .map(|task| task.id.as_str())
+    }
+}
+
+impl fmt::Debug for HaBootstrap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HaBootstrap")
+            .field("encryption", &self.encryption)
+            .field("key_scheme", &self.key_scheme)
+            .field(
+                "shared_key",
+                &self.shared_key.as_ref().map(|_| "<redacted>"),
+            )
+            .field("same_link", &self.same_link)
+            .field("lan_failover", &self.lan_failover)
+            .field("stateful_failover", &self.stateful_failover)
+            .field("extra", &self.extra)
+            .finish()
+    }
+}
+
+impl FailoverLink {
+    /// An IPv4 link on `interface` (a physical interface of the primary).
+    pub fn new(
+        interface: Reference,
+        logical_name: &str,
+        active_ip: &str,
+        standby_ip: &str,
+        subnet_mask: &str,
+    ) -> FailoverLink {
+        FailoverLink {
+            interface,
+            logical_name: logical_name.to_string(),
+            active_ip: active_ip.to_string(),
+            standby_ip: standby_ip.to_string(),
+            subnet_mask: subnet_mask.to_string(),
+            use_ipv6: false,
+            extra: Extra::new(),
+        }
+    }
+
+    /// Addresses are IPv6 and `subnet_mask` is a prefix length.
+    pub fn ipv6(mut self) -> FailoverLink {
+        self.use_ipv6 = true;
+        self
+    }
+}
+
+impl FailoverStatus {
+    pub fn from_pair(pair: &HaPair) -> FailoverStatus {
+        let metadata = pair.metadata.as_ref();
+        FailoverStatus {
+            primary: pair.primary.clone(),
+            secondary: pair.secondary.clone(),
+            primary_state: metadata
+                .and_then(|m| m.primary_status.as_ref())
+                .map(|status| status.current_status.clone()),
+            secondary_state: metadata
+                .and_then(|m| m.secondary_status.as_ref())
+                .map(|status| status.current_status.clone()),
+        }
+    }
+
+    /// The unit currently active, if FMC reports exactly one.
+    pub fn active(&self) -> Option<&Reference> {
+        match (&self.primary_state, &self.secondary_state) {
+            (Some(PeerState::Active), Some(state)) if *state != PeerState::Active => {
+                Some(&self.primary)
+            }
+            (Some(state), Some(PeerState::Active)) if *state != PeerState::Active => {
+                Some(&self.secondary)
+            }
+            _ => None,
+        }
+    }
+
+    /// One unit active and the other ready to take over.
+    pub fn is_healthy(&self) -> bool {
+        matches!(
+            (&self.primary_state, &self.secondary_state),
+            (Some(PeerState::Active), Some(PeerState::Standby))
+                | (Some(PeerState::Standby), Some(PeerState::Active))
+        )
+    }
+}
+
+impl From<String> for PeerState {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Active" => PeerState::Active,
+            "Standby" => PeerState::Standby,
+            "Failed" => PeerState::Failed,
+            _ => PeerState::Other(value),
+        }
+    }
+}
+
+impl From<PeerState> for String {
+    fn from(value: PeerState) -> Self {
+        match value {
+            PeerState::Active => "Active".to_string(),
+            PeerState::Standby => "Standby".to_string(),
+            PeerState::Failed => "Failed".to_string(),
+            PeerState::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::device_ref;
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fn_build_pair() {
+        let interface = |name: &str| Reference {
+            id: format!("intf-{}", name),
+            _type: Some("PhysicalInterface".to_string()),
+            name: Some(name.to_string()),
+        };
+        let pair = HaPair::new(
+            "site-12-ha",
+            device_ref("ftd-a"),
+            device_ref("ftd-b"),
+            FailoverLink::new(
+                interface("GigabitEthernet0/3"),
+                "FOVER",
+                "192.0.2.1",
+                "192.0.2.2",
+                "255.255.255.252",
+            ),
+        )
+        .state_link(FailoverLink::new(
+            interface("GigabitEthernet0/4"),
+            "STATE",
+            "192.0.2.5",
+            "192.0.2.6",
+            "255.255.255.252",
+        ))
+        .shared_key("cisco123");
+        let debug = format!("{:?}", pair);
+        assert!(debug.contains("GigabitEthernet0/4"));
+        assert!(!debug.contains("cisco123"));
+
+        let body = serde_json::to_value(&pair).unwrap();
+        assert_eq!(body["type"], "DeviceHAPair");
+        assert_eq!(body["ftdHABootstrap"]["useSameLinkForFailovers"], false);
+        assert_eq!(body["ftdHABootstrap"]["encKeyGenerationScheme"], "CUSTOM");
+        assert_eq!(
+            body["ftdHABootstrap"]["lanFailover"]["logicalName"],
+            "FOVER"
+        );
+        assert_eq!(
+            body["ftdHABootstrap"]["statefulFailover"]["interfaceObject"]["name"],
+            "GigabitEthernet0/4"
+        );
+        assert!(body.get("metadata").is_none());
+
+        let request = HaActionRequest {
+            id: "ha-1".to_string(),
+            _type: HA_PAIR_TYPE.to_string(),
+            action: HaAction::Break,
+            force_break: Some(false),
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"id": "ha-1", "type": "DeviceHAPair", "action": "HABREAK", "forceBreak": false})
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_failover() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let ha = client.ha_pairs();
+
+        let pairs = ha.list().collect_all().await?;
+        assert_eq!(pairs.len(), 1);
+        let id = pairs[0].id.clone().unwrap();
+
+        let status = ha.failover_status(&id).await?;
+        assert!(status.is_healthy());
+        assert_eq!(status.active(), Some(&status.primary));
+
+        let switched = ha.switch_active(&id).await?;
+        assert_eq!(
+            FailoverStatus::from_pair(&switched).active(),
+            Some(&status.secondary)
+        );
+        let status = ha
+            .wait_healthy(&id, Duration::from_millis(10), Duration::from_secs(1))
+            .await?;
+        assert_eq!(status.active(), Some(&status.secondary));
+        assert!(mock
+            .requests()
+            .iter()
+            .any(|r| r.starts_with("PUT ") && r.ends_with(&format!("/ftddevicehapairs/{}", id))));
+
+        let mut interfaces = ha.monitored_interfaces(&id).collect_all().await?;
+        assert_eq!(interfaces.len(), 2);
+        interfaces[1].monitor_for_failures = true;
+        let saved = ha.update_monitored_interface(&id, &interfaces[1]).await?;
+        assert!(saved.monitor_for_failures);
+
+        let created = ha
+            .create(&HaPair::new(
+                "site-12-ha",
+                device_ref("ftd-c"),
+                device_ref("ftd-d"),
+                FailoverLink::new(
+                    device_ref("intf-1"),
+                    "FOVER",
+                    "192.0.2.1",
+                    "192.0.2.2",
+                    "30",
+                )
+                .ipv6(),
+            ))
+            .await?;
+        let new_id = created.id.unwrap();
+        ha.break_pair(&new_id, true).await?;
+        assert!(matches!(
+            ha.get(&new_id).await,
+            Err(FmcError::Status { .. })
+        ));
+
+        ha.delete(&id).await?;
+        assert!(ha.list().collect_all().await?.is_empty());
+
+        // A pair that never settles is polled up to the deadline, then once more.
+        mock.fixture(
+            "devicehapairs/ftddevicehapairs",
+            vec![json!({
+                "id": "ha-9", "type": "DeviceHAPair", "name": "stuck-ha",
+                "primary": device_ref("ftd-e"), "secondary": device_ref("ftd-f"),
+                "metadata": {
+                    "primaryStatus": {"currentStatus": "Active"},
+                    "secondaryStatus": {"currentStatus": "Failed"}
+                }
+            })],
+        );
+        let start = Instant::now();
+        let timeout = Duration::from_millis(100);
+        let status = ha
+            .wait_healthy("ha-9", Duration::from_millis(40), timeout)
+            .await?;
+        assert!(!status.is_healthy());
+        assert!(start.elapsed() >= timeout);
+        Ok(())
+    }
+}
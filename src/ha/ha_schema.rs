@@ -0,0 +1,171 @@
+use super::{core, Deserialize, Extra, FmcClient, Reference, Serialize, Task};
+
+// /devicehapairs/ftddevicehapairs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HaPair {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    /// The device that starts out active.
+    pub primary: Reference,
+    pub secondary: Reference,
+    /// Link settings. Only sent when the pair is created; FMC leaves
+    /// it out of later replies.
+    #[serde(rename = "ftdHABootstrap", skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<HaBootstrap>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<HaMetadata>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Failover link and encryption settings, sent on create. Debug output
+/// leaves out the shared key.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct HaBootstrap {
+    #[serde(rename = "isEncryptionEnabled")]
+    pub encryption: bool,
+    /// "CUSTOM" with a shared key, or "AUTO".
+    #[serde(
+        rename = "encKeyGenerationScheme",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub key_scheme: Option<String>,
+    #[serde(rename = "sharedKey", skip_serializing_if = "Option::is_none")]
+    pub shared_key: Option<String>,
+    /// Carry state over the failover link instead of a separate one.
+    #[serde(rename = "useSameLinkForFailovers")]
+    pub same_link: bool,
+    #[serde(rename = "lanFailover")]
+    pub lan_failover: FailoverLink,
+    #[serde(rename = "statefulFailover", skip_serializing_if = "Option::is_none")]
+    pub stateful_failover: Option<FailoverLink>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// The failover or state link: an interface on the primary and the
+/// addresses each unit uses on it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FailoverLink {
+    #[serde(rename = "interfaceObject")]
+    pub interface: Reference,
+    #[serde(rename = "logicalName")]
+    pub logical_name: String,
+    #[serde(rename = "activeIP")]
+    pub active_ip: String,
+    #[serde(rename = "standbyIP")]
+    pub standby_ip: String,
+    /// Dotted mask for IPv4, prefix length for IPv6.
+    #[serde(rename = "subnetMask")]
+    pub subnet_mask: String,
+    #[serde(rename = "useIPv6Address")]
+    pub use_ipv6: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HaMetadata {
+    #[serde(rename = "primaryStatus", skip_serializing_if = "Option::is_none")]
+    pub primary_status: Option<PeerStatus>,
+    #[serde(rename = "secondaryStatus", skip_serializing_if = "Option::is_none")]
+    pub secondary_status: Option<PeerStatus>,
+    /// Set on replies to create, break and switch, which run as tasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<Task>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PeerStatus {
+    #[serde(rename = "currentStatus")]
+    pub current_status: PeerState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<Reference>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// A unit's failover role as FMC reports it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum PeerState {
+    Active,
+    Standby,
+    Failed,
+    Other(String),
+}
+
+/// PUT body for pair-wide actions.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct HaActionRequest {
+    pub(crate) id: String,
+    #[serde(rename = "type")]
+    pub(crate) _type: String,
+    pub(crate) action: HaAction,
+    #[serde(rename = "forceBreak", skip_serializing_if = "Option::is_none")]
+    pub(crate) force_break: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) enum HaAction {
+    /// Split the pair back into standalone devices.
+    #[serde(rename = "HABREAK")]
+    Break,
+    /// Make the standby unit active.
+    #[serde(rename = "SWITCH")]
+    Switch,
+}
+
+// /devicehapairs/ftddevicehapairs/{pair}/monitoredinterfaces
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MonitoredInterface {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    /// The interface's logical name, e.g. "outside".
+    pub name: String,
+    /// Whether a failure of this interface triggers failover.
+    #[serde(rename = "monitorForFailures")]
+    pub monitor_for_failures: bool,
+    #[serde(rename = "ipv4Configuration", skip_serializing_if = "Option::is_none")]
+    pub ipv4: Option<MonitoredIpv4>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MonitoredIpv4 {
+    #[serde(rename = "activeIPv4Address", skip_serializing_if = "Option::is_none")]
+    pub active_address: Option<String>,
+    #[serde(rename = "activeIPv4Mask", skip_serializing_if = "Option::is_none")]
+    pub active_mask: Option<String>,
+    /// The standby unit's address; needed to monitor the interface.
+    #[serde(rename = "standbyIPv4Address", skip_serializing_if = "Option::is_none")]
+    pub standby_address: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Which unit FMC last saw in which role.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailoverStatus {
+    pub primary: Reference,
+    pub secondary: Reference,
+    pub primary_state: Option<PeerState>,
+    pub secondary_state: Option<PeerState>,
+}
+
+/// FTD HA pairs, from client.ha_pairs().
+#[derive(Debug)]
+pub struct HaPairs<'c> {
+    pub(super) client: &'c FmcClient,
+}
@@ -0,0 +1,13 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::deployment::deployment_schema::Task;
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra, Reference};
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::{FmcApi, FmcQuery};
+use serde::{Deserialize, Serialize};
+
+mod ha_methods;
+pub mod ha_schema;
+
+/// The "type" FMC gives FTD HA pairs.
+const HA_PAIR_TYPE: &str = "DeviceHAPair";
@@ -0,0 +1,208 @@
+use super::info_schema::{ServerVersionItem, ServerVersions, Version};
+use super::{fmt, FmcError, FromStr, Ordering};
+
+impl Version {
+    /// The dotted numbers, e.g. [6, 6, 0] for "6.6.0 (build 90)".
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
+    }
+
+    pub fn build(&self) -> Option<u32> {
+        self.build
+    }
+
+    /// Whether this is `min` or later.
+    pub fn at_least(&self, min: &Version) -> bool {
+        self >= min
+    }
+}
+
+impl FromStr for Version {
+    type Err = FmcError;
+
+    /// Takes the numbers before any "(...)", and "build N" from inside it.
+    fn from_str(raw: &str) -> Result<Version, FmcError> {
+        let invalid = || FmcError::InvalidVersion(raw.to_string());
+        let (main, detail) = match raw.find('(') {
+            Some(index) => raw.split_at(index),
+            None => (raw, ""),
+        };
+
+        let numbers = main
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<u32>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u32>, FmcError>>()?;
+        if numbers.is_empty() {
+            return Err(invalid());
+        }
+
+        let build = detail.split_once("build").and_then(|(_, rest)| {
+            rest.trim_start()
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        });
+
+        Ok(Version {
+            numbers,
+            build,
+            raw: raw.trim().to_string(),
+        })
+    }
+}
+
+impl Ord for Version {
+    /// Missing trailing numbers count as 0 and a missing build sorts first.
+    fn cmp(&self, other: &Version) -> Ordering {
+        let len = self.numbers.len().max(other.numbers.len());
+        let number = |v: &Version, i| v.numbers.get(i).copied().unwrap_or(0);
+
+        (0..len)
+            .map(|i| number(self, i).cmp(&number(other, i)))
+            .find(|order| *order != Ordering::Equal)
+            .unwrap_or_else(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl ServerVersions {
+    /// Parses every version in the reply. Content versions FMC leaves
+    /// empty or can't be parsed are None; the FMC version must parse.
+    pub fn from_item(item: &ServerVersionItem) -> Result<ServerVersions, FmcError> {
+        let optional = |raw: &Option<String>| raw.as_deref().and_then(|raw| raw.parse().ok());
+
+        Ok(ServerVersions {
+            fmc: item.server_version.parse()?,
+            vdb: optional(&item.vdb_version),
+            sru: optional(&item.sru_version),
+            geo: optional(&item.geo_version),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::{Fault, MockFmc};
+    use super::super::super::mock::MOCK_SERVER_VERSION;
+    use super::super::super::uri::uri_schema::FmcApi;
+    use super::*;
+
+    fn version(raw: &str) -> Version {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn fn_parse_versions() {
+        let item: ServerVersionItem = serde_json::from_str(
+            r#"{"serverVersion": "6.6.0 (build 90)",
+                "vdbVersion": "build 336 ( 2020-05-18 20:46:45 )",
+                "sruVersion": "2020-05-18-001-vrt",
+                "geoVersion": "2020-05-12-001",
+                "type": "ServerVersion"}"#,
+        )
+        .unwrap();
+        let versions = ServerVersions::from_item(&item).unwrap();
+
+        assert_eq!(versions.fmc.numbers(), &[6, 6, 0]);
+        assert_eq!(versions.fmc.build(), Some(90));
+        assert_eq!(versions.fmc.to_string(), "6.6.0 (build 90)");
+        assert_eq!(versions.vdb.unwrap().numbers(), &[336]);
+        assert_eq!(versions.sru.unwrap().numbers(), &[2020, 5, 18, 1]);
+        assert_eq!(versions.geo.unwrap().numbers(), &[2020, 5, 12, 1]);
+
+        assert!(matches!(
+            "unknown".parse::<Version>(),
+            Err(FmcError::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn fn_compare_versions() {
+        assert_eq!(version("6.6"), version("6.6.0"));
+        assert!(version("6.6.0") < version("6.6.0 (build 90)"));
+        assert!(version("6.6.0 (build 90)") < version("6.6.0 (build 113)"));
+        assert!(version("6.6.0 (build 113)") < version("6.6.1"));
+        assert!(version("6.10") > version("6.9.0.2"));
+        assert!(version("7.0.1 (build 84)").at_least(&version("6.7")));
+        assert!(!version("6.2.3").at_least(&version("6.3")));
+    }
+
+    #[tokio::test]
+    async fn fn_version_gating() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        client.login().await?;
+        assert_eq!(
+            client.server_version().await?.fmc,
+            version(MOCK_SERVER_VERSION)
+        );
+        assert!(client.supports(&FmcApi::Tid).await?);
+
+        let mock = MockFmc::start().await;
+        mock.set_version("6.2.3 (build 84)");
+        let client = mock.client().await;
+        assert!(!client.supports(&FmcApi::TaxiiConfig).await?);
+
+        let err = client.request().await?.get(FmcApi::Tid).await.unwrap_err();
+        match err {
+            FmcError::UnsupportedVersion {
+                endpoint,
+                required,
+                found,
+            } => {
+                assert_eq!(endpoint, "Tid");
+                assert_eq!(required, "6.3");
+                assert_eq!(found, "6.2.3 (build 84)");
+            }
+            other => panic!("expected UnsupportedVersion, got {:?}", other),
+        }
+        // Checked before anything is sent.
+        assert!(!mock.requests().iter().any(|r| r.ends_with("/tid")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_version_unknown() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        mock.set_version("6.2.3 (build 84)");
+        mock.fail(Fault::new(403).path("serverversion"));
+        let client = mock.client().await;
+
+        // Without a version nothing is gated, and it is only asked once.
+        client.login().await?;
+        assert_eq!(client.devices().list().await?.items.len(), 3);
+        assert!(client.request().await?.get(FmcApi::Tid).await.is_ok());
+        let lookups = |mock: &MockFmc| {
+            mock.requests()
+                .iter()
+                .filter(|r| r.contains("/info/serverversion"))
+                .count()
+        };
+        assert_eq!(lookups(&mock), 1);
+
+        // Asking for it explicitly retries the lookup.
+        assert!(!client.supports(&FmcApi::Tid).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use super::{Deserialize, Extra, Serialize};
+
+// /info/serverversion
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerVersionItem {
+    /// e.g. "6.6.0 (build 90)"
+    #[serde(rename = "serverVersion")]
+    pub server_version: String,
+    /// e.g. "build 336 ( 2020-05-18 20:46:45 )"
+    #[serde(rename = "vdbVersion", skip_serializing_if = "Option::is_none")]
+    pub vdb_version: Option<String>,
+    /// e.g. "2020-05-18-001-vrt"
+    #[serde(rename = "sruVersion", skip_serializing_if = "Option::is_none")]
+    pub sru_version: Option<String>,
+    /// e.g. "2020-05-12-001"
+    #[serde(rename = "geoVersion", skip_serializing_if = "Option::is_none")]
+    pub geo_version: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// A version string reduced to its numbers so versions compare the way
+/// FMC releases do: "6.6" == "6.6.0" < "6.6.0 (build 90)" < "6.6.1".
+/// Displays as the original string.
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub(super) numbers: Vec<u32>,
+    pub(super) build: Option<u32>,
+    pub(super) raw: String,
+}
+
+/// What the FMC and its content updates run, from /info/serverversion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerVersions {
+    pub fmc: Version,
+    /// Vulnerability database.
+    pub vdb: Option<Version>,
+    /// Snort rule update.
+    pub sru: Option<Version>,
+    /// Geolocation database.
+    pub geo: Option<Version>,
+}
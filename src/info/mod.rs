@@ -0,0 +1,9 @@
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::Extra;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+mod info_methods;
+pub mod info_schema;
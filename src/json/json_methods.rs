@@ -1 +1,79 @@
+use serde::de::DeserializeOwned;
 
+use super::super::error::error_schema::FmcError;
+use super::json_schema::devices::{FtdMode, HealthStatus, LicenseCap};
+
+/// Deserializes an FMC reply, reporting the JSON path of any field
+/// that doesn't match the schema.
+pub(crate) fn decode<T: DeserializeOwned>(body: &str) -> Result<T, FmcError> {
+    let de = &mut serde_json::Deserializer::from_str(body);
+    Ok(serde_path_to_error::deserialize(de)?)
+}
+
+impl From<String> for HealthStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "green" => HealthStatus::Green,
+            "yellow" => HealthStatus::Yellow,
+            "red" => HealthStatus::Red,
+            _ => HealthStatus::Other(value),
+        }
+    }
+}
+
+impl From<HealthStatus> for String {
+    fn from(value: HealthStatus) -> Self {
+        match value {
+            HealthStatus::Green => "green".to_string(),
+            HealthStatus::Yellow => "yellow".to_string(),
+            HealthStatus::Red => "red".to_string(),
+            HealthStatus::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for FtdMode {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "ROUTED" => FtdMode::Routed,
+            "TRANSPARENT" => FtdMode::Transparent,
+            _ => FtdMode::Other(value),
+        }
+    }
+}
+
+impl From<FtdMode> for String {
+    fn from(value: FtdMode) -> Self {
+        match value {
+            FtdMode::Routed => "ROUTED".to_string(),
+            FtdMode::Transparent => "TRANSPARENT".to_string(),
+            FtdMode::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for LicenseCap {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "BASE" => LicenseCap::Base,
+            "MALWARE" => LicenseCap::Malware,
+            "THREAT" => LicenseCap::Threat,
+            "URLFilter" => LicenseCap::UrlFilter,
+            "CARRIER" => LicenseCap::Carrier,
+            _ => LicenseCap::Other(value),
+        }
+    }
+}
+
+impl From<LicenseCap> for String {
+    fn from(value: LicenseCap) -> Self {
+        match value {
+            LicenseCap::Base => "BASE".to_string(),
+            LicenseCap::Malware => "MALWARE".to_string(),
+            LicenseCap::Threat => "THREAT".to_string(),
+            LicenseCap::UrlFilter => "URLFilter".to_string(),
+            LicenseCap::Carrier => "CARRIER".to_string(),
+            LicenseCap::Other(value) => value,
+        }
+    }
+}
@@ -1,141 +1,324 @@
 // Core JSON blocks that are used in many places
-pub(super) mod core {
-    use super::super::Deserialize;
-
-    #[derive(Deserialize, Debug)]
-    pub(super) struct Domain {
-        name: String,
-        id: String,
-        uuid: Option<String>,
+//
+// Field names follow FMC's JSON via `rename`, so every model can be sent back
+// with PUT/POST. Read-only blocks (links, metadata) are skipped on write, and
+// writable items keep any attributes this crate doesn't model in `extra`.
+pub mod core {
+    use super::super::{Deserialize, Serialize};
+    use serde::de::DeserializeOwned;
+    use serde_json::{Map, Value};
+
+    /// Attributes FMC sent that aren't modelled, sent back untouched on PUT.
+    pub type Extra = Map<String, Value>;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Domain {
+        pub name: String,
+        pub id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub uuid: Option<String>,
+        #[serde(flatten)]
+        pub extra: Extra,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Ipv6 {
+        #[serde(rename = "enforceEUI64", alias = "enforeEUI64")]
+        pub enforce_eui64: bool,
+        #[serde(rename = "enableAutoConfig", alias = "enforceAutoConfig")]
+        pub enable_auto_config: bool,
+        #[serde(rename = "enableDHCPAddrConfig")]
+        pub enable_dhcp_addr_config: bool,
+        #[serde(rename = "enableDHCPNonAddrConfig")]
+        pub enable_dhcp_nonaddr_config: bool,
+        #[serde(rename = "dadAttempts")]
+        pub dad_attempts: u32,
+        #[serde(rename = "nsInterval")]
+        pub ns_interval: u32,
+        #[serde(rename = "reachableTime")]
+        pub reachable_time: u32,
+        #[serde(rename = "enableRA")]
+        pub enable_ra: bool,
+        #[serde(rename = "raLifeTime")]
+        pub ra_lifetime: u32,
+        #[serde(rename = "raInterval")]
+        pub ra_interval: u32,
+        #[serde(rename = "enableIPV6")]
+        pub enable_ipv6: bool,
+        #[serde(flatten)]
+        pub extra: Extra,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct Ipv6 {
-        #[serde(alias = "enforeEUI64")]
-        enforce_eui64: bool,
-        #[serde(alias = "enforceAutoConfig")]
-        enable_auto_config: bool,
-        #[serde(alias = "enableDHCPAddrConfig")]
-        enable_dhcp_addr_config: bool,
-        #[serde(alias = "enableDHCPNonAddrConfig")]
-        enable_dhcp_nonaddr_config: bool,
-        #[serde(alias = "dadAttempts")]
-        dad_attempts: u32,
-        #[serde(alias = "nsInterval")]
-        ns_interval: u32,
-        #[serde(alias = "reachableTime")]
-        reachable_time: u32,
-        #[serde(alias = "enableRA")]
-        enable_ra: bool,
-        #[serde(alias = "raLifeTime")]
-        ra_lifetime: u32,
-        #[serde(alias = "raInterval")]
-        ra_interval: u32,
-        #[serde(alias = "enableIPV6")]
-        enable_ipv6: bool,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Links {
+        #[serde(rename = "self")]
+        pub _self: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub parent: Option<String>,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct Links {
-        #[serde(alias = "self")]
-        _self: String,
-        parent: Option<String>,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct LastUser {
+        pub name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub _type: Option<String>,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct LastUser {
-        name: String,
-        id: Option<String>,
-        #[serde(alias = "type")]
-        _type: Option<String>,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct MetaData {
+        #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+        pub read_only: Option<ReadOnly>,
+        #[serde(rename = "lastUser", skip_serializing_if = "Option::is_none")]
+        pub last_user: Option<LastUser>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub domain: Option<Domain>,
+        #[serde(flatten)]
+        pub extra: Extra,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct MetaData {
-        #[serde(alias = "readOnly")]
-        read_only: Option<ReadOnly>,
-        #[serde(alias = "lastUser")]
-        last_user: Option<LastUser>,
-        domain: Option<Domain>,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+    pub struct Paging {
+        pub offset: u32,
+        pub limit: u32,
+        pub count: u32,
+        pub pages: u32,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct Paging {
-        offset: u16,
-        limit: u16,
-        count: u16,
-        pages: u16,
+    /// One page of a collection endpoint (anything with items and paging).
+    pub trait Collection: DeserializeOwned {
+        type Item;
+
+        fn paging(&self) -> &Paging;
+        fn into_items(self) -> Vec<Self::Item>;
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct ReadOnly {
+        pub state: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reason: Option<String>,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct ReadOnly {
-        state: bool,
-        reason: String,
+    /// A pointer to another FMC object, e.g. a device's accessPolicy.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct Reference {
+        pub id: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub _type: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct SecurityZone {
-        id: String,
-        #[serde(alias = "type")]
-        _type: String,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct SecurityZone {
+        pub id: String,
+        #[serde(rename = "type")]
+        pub _type: String,
+        #[serde(flatten)]
+        pub extra: Extra,
     }
 }
 
-pub(crate) mod devices {
+pub mod devices {
     use super::core;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     // /api/fmc_config/v1/domain/{domainUUID}/devices/devicerecords
-    #[derive(Deserialize, Debug)]
-    pub(crate) struct DeviceRecords {
-        links: core::Links,
-        items: Vec<DeviceItem>,
-        paging: core::Paging,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct DeviceRecords {
+        pub links: core::Links,
+        #[serde(default)]
+        pub items: Vec<DeviceItem>,
+        pub paging: core::Paging,
+    }
+
+    impl core::Collection for DeviceRecords {
+        type Item = DeviceItem;
+
+        fn paging(&self) -> &core::Paging {
+            &self.paging
+        }
+
+        fn into_items(self) -> Vec<DeviceItem> {
+            self.items
+        }
+    }
+
+    // Fields after `name` are only present with ?expanded=true
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct DeviceItem {
+        pub id: String,
+        #[serde(rename = "type")]
+        pub _type: String,
+        #[serde(default, skip_serializing)]
+        pub links: Option<core::Links>,
+        pub name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        #[serde(rename = "hostName", skip_serializing_if = "Option::is_none")]
+        pub host_name: Option<String>,
+        #[serde(default, skip_serializing)]
+        pub model: Option<String>,
+        #[serde(rename = "modelId", default, skip_serializing)]
+        pub model_id: Option<String>,
+        #[serde(rename = "modelNumber", default, skip_serializing)]
+        pub model_number: Option<String>,
+        #[serde(rename = "modelType", default, skip_serializing)]
+        pub model_type: Option<String>,
+        #[serde(rename = "healthStatus", default, skip_serializing)]
+        pub health_status: Option<HealthStatus>,
+        #[serde(rename = "sw_version", default, skip_serializing)]
+        pub sw_version: Option<String>,
+        #[serde(rename = "healthPolicy", skip_serializing_if = "Option::is_none")]
+        pub health_policy: Option<core::Reference>,
+        #[serde(rename = "accessPolicy", skip_serializing_if = "Option::is_none")]
+        pub access_policy: Option<core::Reference>,
+        #[serde(rename = "license_caps", skip_serializing_if = "Option::is_none")]
+        pub license_caps: Option<Vec<LicenseCap>>,
+        #[serde(rename = "keepLocalEvents", skip_serializing_if = "Option::is_none")]
+        pub keep_local_events: Option<bool>,
+        #[serde(
+            rename = "prohibitPacketTransfer",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub prohibit_packet_transfer: Option<bool>,
+        #[serde(rename = "ftdMode", skip_serializing_if = "Option::is_none")]
+        pub ftd_mode: Option<FtdMode>,
+        #[serde(default, skip_serializing)]
+        pub metadata: Option<DeviceMetaData>,
+        #[serde(flatten)]
+        pub extra: core::Extra,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct DeviceMetaData {
+        #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+        pub read_only: Option<core::ReadOnly>,
+        #[serde(rename = "inventoryData", skip_serializing_if = "Option::is_none")]
+        pub inventory_data: Option<InventoryData>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub domain: Option<core::Domain>,
+        #[serde(rename = "isPartOfContainer", skip_serializing_if = "Option::is_none")]
+        pub is_part_of_container: Option<bool>,
+        #[serde(rename = "isMultiInstance", skip_serializing_if = "Option::is_none")]
+        pub is_multi_instance: Option<bool>,
+        #[serde(flatten)]
+        pub extra: core::Extra,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct InventoryData {
+        #[serde(rename = "cpuCores", skip_serializing_if = "Option::is_none")]
+        pub cpu_cores: Option<String>,
+        #[serde(rename = "cpuType", skip_serializing_if = "Option::is_none")]
+        pub cpu_type: Option<String>,
+        #[serde(rename = "memoryInMB", skip_serializing_if = "Option::is_none")]
+        pub memory_in_mb: Option<String>,
+        #[serde(flatten)]
+        pub extra: core::Extra,
+    }
+
+    // Enums below keep values they don't recognise in Other(..) so that
+    // newer FMC releases still parse and round-trip.
+
+    /// healthStatus: "green", "yellow", "red", ...
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(from = "String", into = "String")]
+    pub enum HealthStatus {
+        Green,
+        Yellow,
+        Red,
+        Other(String),
+    }
+
+    /// ftdMode: "ROUTED" or "TRANSPARENT"
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(from = "String", into = "String")]
+    pub enum FtdMode {
+        Routed,
+        Transparent,
+        Other(String),
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct DeviceItem {
-        id: String,
-        #[serde(alias = "type")]
-        _type: String,
-        links: core::Links,
-        name: String,
-        #[serde(alias = "hostName")]
-        host_name: Option<String>,
-        #[serde(alias = "ftdMode")]
-        ftd_mode: Option<String>,
+    /// license_caps entries: "BASE", "MALWARE", "THREAT", "URLFilter", ...
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    #[serde(from = "String", into = "String")]
+    pub enum LicenseCap {
+        Base,
+        Malware,
+        Threat,
+        UrlFilter,
+        Carrier,
+        Other(String),
     }
 
     // /api/fmc_config/v1/domain/{domainUUID}/devices/devicerecords/{containerUUID}/physicalinterfaces
-    #[derive(Deserialize, Debug)]
-    pub(super) struct PhysicalInterfaces {
-        links: core::Links,
-        items: Vec<PhysIntItem>,
-        paging: core::Paging,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct PhysicalInterfaces {
+        pub links: core::Links,
+        #[serde(default)]
+        pub items: Vec<PhysIntItem>,
+        pub paging: core::Paging,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct PhysIntItem {
-        links: core::Links,
-        name: String,
-        id: String,
+    impl core::Collection for PhysicalInterfaces {
+        type Item = PhysIntItem;
+
+        fn paging(&self) -> &core::Paging {
+            &self.paging
+        }
+
+        fn into_items(self) -> Vec<PhysIntItem> {
+            self.items
+        }
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct EtherChannelInts {
-        links: core::Links,
-        items: Vec<EtherChannelInt>,
-        paging: core::Paging,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct PhysIntItem {
+        #[serde(default, skip_serializing)]
+        pub links: Option<core::Links>,
+        pub name: String,
+        pub id: String,
+        #[serde(default, skip_serializing)]
+        pub metadata: Option<core::MetaData>,
+        #[serde(flatten)]
+        pub extra: core::Extra,
     }
 
-    #[derive(Deserialize, Debug)]
-    pub(super) struct EtherChannelInt {
-        links: core::Links,
-        name: String,
-        id: String,
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct EtherChannelInts {
+        pub links: core::Links,
+        #[serde(default)]
+        pub items: Vec<EtherChannelInt>,
+        pub paging: core::Paging,
+    }
+
+    impl core::Collection for EtherChannelInts {
+        type Item = EtherChannelInt;
+
+        fn paging(&self) -> &core::Paging {
+            &self.paging
+        }
+
+        fn into_items(self) -> Vec<EtherChannelInt> {
+            self.items
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    pub struct EtherChannelInt {
+        #[serde(default, skip_serializing)]
+        pub links: Option<core::Links>,
+        pub name: String,
+        pub id: String,
+        #[serde(default, skip_serializing)]
+        pub metadata: Option<core::MetaData>,
+        #[serde(flatten)]
+        pub extra: core::Extra,
     }
 }
-/*
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,7 +441,26 @@ mod tests {
           "#;
 
         let parsed: devices::DeviceRecords = serde_json::from_str(raw_string).unwrap();
-        println!("{:#?}\n\n", parsed);
+        assert_eq!(parsed.items.len(), 2);
+        assert_eq!(parsed.paging.count, 2);
+        let cat = &parsed.items[0];
+        assert_eq!(cat.name, "Cat");
+        assert_eq!(cat.host_name.as_deref(), Some("10.17.11.155"));
+        assert_eq!(cat.health_status, Some(devices::HealthStatus::Green));
+        assert_eq!(cat.ftd_mode, Some(devices::FtdMode::Routed));
+        assert_eq!(cat.license_caps, Some(vec![devices::LicenseCap::Base]));
+        assert_eq!(
+            cat.access_policy
+                .as_ref()
+                .map(|policy| policy.name.as_deref()),
+            Some(Some("Basic IPS"))
+        );
+        let inventory = cat.metadata.as_ref().unwrap().inventory_data.as_ref();
+        assert_eq!(
+            inventory.and_then(|data| data.memory_in_mb.as_deref()),
+            Some("8192")
+        );
+        assert_eq!(parsed.items[1].name, "Poop");
     }
 
     // GET /api/fmc_config/v1/domain/{domainUUID}/devices/devicerecords/{containerUUID}/physicalinterfaces
@@ -308,7 +510,18 @@ mod tests {
         }
         "#;
         let parsed: devices::PhysicalInterfaces = serde_json::from_str(raw_string).unwrap();
-        println!("{:#?}\n\n", parsed);
+        let names: Vec<&str> = parsed.items.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "GigabitEthernet1/1",
+                "GigabitEthernet1/2",
+                "GigabitEthernet1/3",
+                "GigabitEthernet1/4"
+            ]
+        );
+        assert_eq!(parsed.items[3].id, "PhyIntfId4");
+        assert_eq!(parsed.paging.limit, 4);
     }
 
     // GET /fmc_config/v1/domain/DomainUUID/devices/devicerecords/containerUUID/etherchannelinterfaces
@@ -342,7 +555,105 @@ mod tests {
             }
         }"#;
         let parsed: devices::EtherChannelInts = serde_json::from_str(raw_str).unwrap();
-        println!("{:#?}\n\n", parsed);
+        assert_eq!(parsed.items.len(), 2);
+        assert_eq!(parsed.items[0].name, "Port-channel2");
+        assert_eq!(parsed.items[1].id, "etherChannelIntfUUID1");
+        assert_eq!(parsed.paging.pages, 1);
+    }
+
+    // GET-modify-PUT must keep attributes the models don't know about
+    #[test]
+    fn device_round_trip_test() {
+        let raw_str = r#"{
+            "id": "966e41da-a0f7-11ea-b9d2-a72388220492",
+            "type": "Device",
+            "links": {
+                "self": "https://10.17.11.151/api/fmc_config/v1/domain/e276abec-e0f2-11e3-8169-6d9ed49b625f/devices/devicerecords/966e41da-a0f7-11ea-b9d2-a72388220492"
+            },
+            "name": "Cat",
+            "hostName": "10.17.11.155",
+            "prohibitPacketTransfer": true,
+            "someFutureField": {"nested": [1, 2, 3]},
+            "metadata": {
+                "readOnly": {"state": false},
+                "isPartOfContainer": false
+            }
+        }"#;
+
+        let mut parsed: devices::DeviceItem = serde_json::from_str(raw_str).unwrap();
+        parsed.name = "Dog".to_string();
+
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "id": "966e41da-a0f7-11ea-b9d2-a72388220492",
+                "type": "Device",
+                "name": "Dog",
+                "hostName": "10.17.11.155",
+                "prohibitPacketTransfer": true,
+                "someFutureField": {"nested": [1, 2, 3]}
+            })
+        );
+    }
+
+    // GET ...devicerecords?expanded=true, single item
+    #[test]
+    fn device_expanded_test() {
+        let raw_str = r#"{
+            "id": "966e41da-a0f7-11ea-b9d2-a72388220492",
+            "type": "Device",
+            "name": "Cat",
+            "model": "Cisco Firepower Threat Defense for VMWare",
+            "healthStatus": "green",
+            "sw_version": "6.5.0",
+            "accessPolicy": {
+                "name": "Basic IPS",
+                "id": "000c290c-de43-0ed3-0000-004294967320",
+                "type": "AccessPolicy"
+            },
+            "license_caps": ["BASE", "URLFilter", "SOMETHING_NEW"],
+            "ftdMode": "ROUTED",
+            "metadata": {
+                "inventoryData": {
+                    "cpuCores": "1 CPU (4 cores)",
+                    "memoryInMB": "8192"
+                },
+                "isMultiInstance": false
+            }
+        }"#;
+
+        let parsed: devices::DeviceItem = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.health_status, Some(devices::HealthStatus::Green));
+        assert_eq!(parsed.ftd_mode, Some(devices::FtdMode::Routed));
+        assert_eq!(
+            parsed.license_caps,
+            Some(vec![
+                devices::LicenseCap::Base,
+                devices::LicenseCap::UrlFilter,
+                devices::LicenseCap::Other("SOMETHING_NEW".to_string()),
+            ])
+        );
+        assert_eq!(
+            parsed.access_policy.as_ref().unwrap().name.as_deref(),
+            Some("Basic IPS")
+        );
+
+        let inventory = parsed
+            .metadata
+            .as_ref()
+            .unwrap()
+            .inventory_data
+            .as_ref()
+            .unwrap();
+        assert_eq!(inventory.memory_in_mb.as_deref(), Some("8192"));
+
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(
+            written["license_caps"],
+            serde_json::json!(["BASE", "URLFilter", "SOMETHING_NEW"])
+        );
+        assert_eq!(written["ftdMode"], "ROUTED");
+        assert!(written.get("healthStatus").is_none());
     }
 }
-*/
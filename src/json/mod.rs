@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 mod json_methods;
 pub mod json_schema;
+
+pub(crate) use json_methods::decode;
@@ -1,15 +1,47 @@
+pub mod audit;
+pub mod client;
+pub mod cluster;
+pub mod deployment;
+pub mod device_group;
+pub mod domain;
+pub mod error;
+pub mod ha;
+pub mod info;
 pub mod json;
+pub mod limit;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock_taxii;
+pub mod objects;
+pub mod policy;
+pub mod retry;
 pub mod session;
+pub mod taxii;
+pub mod tid;
+pub mod tls;
 pub mod uri;
 
-use base64::encode;
-use uuid::Uuid;
+use chrono::{DateTime, Duration, Utc};
 use http::HeaderMap;
-use hyper::{Client, Request, Version, http::HeaderValue};
-use hyper_tls::HttpsConnector;
-use chrono::{DateTime, FixedOffset, Duration, Utc};
+use hyper::{http::HeaderValue, Body, Client, Request, Response, Version};
+use tls::tls_schema::FmcConnector;
+use uuid::Uuid;
 
 type FMCUri = String;
+type HttpsClient = Client<FmcConnector>;
+
+pub use client::client_schema::{Devices, FmcClient, Paginator};
+pub use domain::domain_schema::DomainInfo;
+pub use error::error_schema::FmcError;
+pub use info::info_schema::ServerVersions;
+pub use limit::limit_schema::RateLimit;
+pub use retry::retry_schema::{ExponentialBackoff, NoRetry, RetryPolicy};
+pub use session::collect_body;
+pub use session::session_schema::{FmcRequest, SessionCreds};
+pub use tls::tls_schema::TlsConfig;
+pub use uri::uri_schema::{AccessPolicyPath, DevicePath, Filter, FmcApi, FmcQuery, Object};
+pub use uri::uri_schema::{TaxiiResource, TidResource};
 
 #[cfg(test)]
 mod tests {
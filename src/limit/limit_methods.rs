@@ -0,0 +1,106 @@
+use super::limit_schema::{Bucket, LimitPermit, RateLimit, RateLimiter};
+use super::{delay_for, Duration, Instant, Mutex, Semaphore};
+use super::{FMC_MAX_CONCURRENT, FMC_REQUESTS_PER_MINUTE};
+
+impl RateLimit {
+    /// 120 requests per minute, 10 at a time.
+    pub fn new() -> RateLimit {
+        RateLimit {
+            requests_per_minute: FMC_REQUESTS_PER_MINUTE,
+            max_concurrent: FMC_MAX_CONCURRENT,
+        }
+    }
+
+    /// Lower this when other tools share the same API user.
+    pub fn requests_per_minute(mut self, requests: u32) -> RateLimit {
+        self.requests_per_minute = requests.max(1);
+        self
+    }
+
+    pub fn max_concurrent(mut self, requests: usize) -> RateLimit {
+        self.max_concurrent = requests.max(1);
+        self
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit::new()
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> RateLimiter {
+        RateLimiter {
+            bucket: Mutex::new(Bucket::new(&limit, Instant::now())),
+            slots: Semaphore::new(limit.max_concurrent),
+        }
+    }
+
+    /// Waits for a free connection slot and a token from the bucket.
+    pub async fn acquire(&self) -> LimitPermit<'_> {
+        let slot = self.slots.acquire().await;
+        loop {
+            let wait = self.bucket.lock().await.take(Instant::now());
+            match wait {
+                Some(wait) => delay_for(wait).await,
+                None => return LimitPermit { _slot: slot },
+            }
+        }
+    }
+}
+
+impl Bucket {
+    /// Starts with a burst of max_concurrent tokens and refills so that
+    /// no 60 second window sees more than requests_per_minute requests.
+    fn new(limit: &RateLimit, now: Instant) -> Bucket {
+        let capacity = (limit.max_concurrent as u32).min(limit.requests_per_minute);
+        let per_minute = (limit.requests_per_minute - capacity).max(1);
+        Bucket {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            rate: per_minute as f64 / 60.0,
+            updated: now,
+        }
+    }
+
+    /// Takes a token, or says how long until one is available.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+
+        // Tolerate float rounding so waiting exactly `wait` is enough.
+        if self.tokens + 1e-9 >= 1.0 {
+            self.tokens = (self.tokens - 1.0).max(0.0);
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fn_bucket() {
+        let start = Instant::now();
+        let limit = RateLimit::new();
+        let mut bucket = Bucket::new(&limit, start);
+
+        for _ in 0..10 {
+            assert_eq!(bucket.take(start), None);
+        }
+        // 110 more per minute: one token every 545ms.
+        let wait = bucket.take(start).unwrap();
+        assert!(wait > Duration::from_millis(540) && wait < Duration::from_millis(550));
+        assert_eq!(bucket.take(start + wait), None);
+
+        let mut bucket = Bucket::new(&limit.requests_per_minute(1), start);
+        assert_eq!(bucket.take(start), None);
+        let wait = bucket.take(start).unwrap();
+        assert_eq!(wait.as_secs(), 60);
+    }
+}
@@ -0,0 +1,30 @@
+use super::{Instant, Mutex, Semaphore, SemaphorePermit};
+
+/// Pacing for one FmcClient. RateLimit::new() matches FMC's own limits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    pub(super) requests_per_minute: u32,
+    pub(super) max_concurrent: usize,
+}
+
+/// Token bucket plus concurrency cap, shared by every request of a client.
+#[derive(Debug)]
+pub struct RateLimiter {
+    pub(super) bucket: Mutex<Bucket>,
+    pub(super) slots: Semaphore,
+}
+
+#[derive(Debug)]
+pub(super) struct Bucket {
+    pub(super) tokens: f64,
+    pub(super) capacity: f64,
+    /// Tokens added per second.
+    pub(super) rate: f64,
+    pub(super) updated: Instant,
+}
+
+/// Held while a request is in flight; frees a concurrency slot on drop.
+#[derive(Debug)]
+pub struct LimitPermit<'a> {
+    pub(super) _slot: SemaphorePermit<'a>,
+}
@@ -0,0 +1,12 @@
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio::time::{delay_for, Instant};
+
+mod limit_methods;
+pub mod limit_schema;
+
+/// FMC answers 429 once a user exceeds 120 requests per minute.
+const FMC_REQUESTS_PER_MINUTE: u32 = 120;
+
+/// FMC allows at most 10 simultaneous connections per user.
+const FMC_MAX_CONCURRENT: usize = 10;
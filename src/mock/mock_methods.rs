@@ -0,0 +1,855 @@
+use super::mock_schema::{Fault, MockFmc, MockState};
+use super::{json, Body, FmcClient, Method, Reference, Request, Response, StatusCode, Uuid, Value};
+use super::{FmcStream, TlsConfig};
+use super::{
+    DEFAULT_LIMIT, FIXTURES, MOCK_DOMAIN_UUID, MOCK_PASSWORD, MOCK_SUBDOMAINS, MOCK_USERNAME,
+};
+use super::{MOCK_CA_PEM, MOCK_CERT_PEM, MOCK_KEY_PEM, MOCK_SERVER_VERSION};
+use futures::stream;
+use hyper::header::HeaderMap;
+use hyper::server::accept;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::Server;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+/// FMC allows three refreshes per access token chain.
+const MAX_REFRESHES: u8 = 3;
+
+impl MockFmc {
+    /// Starts a mock on a free local port, loaded with the bundled fixtures.
+    pub async fn start() -> MockFmc {
+        MockFmc::serve(None).await
+    }
+
+    /// Like start(), but over HTTPS with a certificate for 127.0.0.1
+    /// issued by MOCK_CA_PEM.
+    pub async fn start_tls() -> MockFmc {
+        let identity = native_tls::Identity::from_pkcs8(MOCK_CERT_PEM, MOCK_KEY_PEM)
+            .expect("mock FMC identity");
+        let acceptor = native_tls::TlsAcceptor::new(identity).expect("mock FMC acceptor");
+        MockFmc::serve(Some(acceptor.into())).await
+    }
+
+    async fn serve(acceptor: Option<tokio_native_tls::TlsAcceptor>) -> MockFmc {
+        let tls = acceptor.is_some();
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock FMC");
+        let addr = listener.local_addr().expect("mock FMC address");
+        let scheme = if tls { "https" } else { "http" };
+        let state = Arc::new(Mutex::new(MockState::new(scheme)));
+
+        // Connections that fail the TLS handshake are dropped.
+        let incoming = stream::unfold(listener, move |mut listener| {
+            let acceptor = acceptor.clone();
+            async move {
+                loop {
+                    let tcp = match listener.accept().await {
+                        Ok((tcp, _)) => tcp,
+                        Err(e) => return Some((Err(e), listener)),
+                    };
+                    let conn = match &acceptor {
+                        None => FmcStream::Http(tcp),
+                        Some(acceptor) => match acceptor.accept(tcp).await {
+                            Ok(tls) => FmcStream::Https(tls),
+                            Err(_) => continue,
+                        },
+                    };
+                    return Some((Ok::<_, io::Error>(conn), listener));
+                }
+            }
+        });
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+
+        let (shutdown, stop) = oneshot::channel::<()>();
+        let server = Server::builder(accept::from_stream(incoming))
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                stop.await.ok();
+            });
+        tokio::spawn(server);
+
+        MockFmc {
+            addr,
+            tls,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Base URL to pass as the host, e.g. "http://127.0.0.1:53211".
+    pub fn url(&self) -> String {
+        match self.tls {
+            true => format!("https://{}", self.addr),
+            false => format!("http://{}", self.addr),
+        }
+    }
+
+    /// An FmcClient pointed at this mock with the mock's credentials,
+    /// trusting MOCK_CA_PEM.
+    pub async fn client(&self) -> FmcClient {
+        let tls = TlsConfig::new()
+            .root_ca_pem(MOCK_CA_PEM)
+            .expect("mock CA certificate");
+        FmcClient::with_tls(&self.url(), MOCK_USERNAME, MOCK_PASSWORD, tls)
+            .await
+            .expect("mock FMC client")
+    }
+
+    /// Replaces the collection at `path` (under the domain, e.g.
+    /// "object/ranges") with these expanded items.
+    pub fn fixture(&self, path: &str, items: Vec<Value>) {
+        self.fixture_in(MOCK_DOMAIN_UUID, path, items);
+    }
+
+    /// Like fixture(), for the domain with UUID `domain`.
+    pub fn fixture_in(&self, domain: &str, path: &str, items: Vec<Value>) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .collections
+            .insert(format!("{}/{}", domain, path), items);
+    }
+
+    /// Queues an error reply for the next matching request(s).
+    pub fn fail(&self, fault: Fault) {
+        self.state.lock().unwrap().faults.push_back(fault);
+    }
+
+    /// Changes the FMC version /info/serverversion reports.
+    pub fn set_version(&self, version: &str) {
+        self.state.lock().unwrap().server_version = version.to_string();
+    }
+
+    /// Invalidates every access and refresh token, as if they expired.
+    pub fn expire_tokens(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.access_tokens.clear();
+        state.refresh_tokens.clear();
+    }
+
+    /// Every request seen so far, as "METHOD /path?query".
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockFmc {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+impl Fault {
+    /// Fails the next request, whatever it is, with `status`.
+    pub fn new(status: u16) -> Fault {
+        Fault {
+            status: StatusCode::from_u16(status).expect("valid HTTP status"),
+            path: None,
+            retry_after: None,
+            times: 1,
+        }
+    }
+
+    /// Only fail requests whose path contains `part`.
+    pub fn path(mut self, part: &str) -> Fault {
+        self.path = Some(part.to_string());
+        self
+    }
+
+    /// Sends a Retry-After header with the error.
+    pub fn retry_after(mut self, seconds: u64) -> Fault {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    /// Fails this many matching requests instead of one.
+    pub fn times(mut self, times: usize) -> Fault {
+        self.times = times;
+        self
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<MockState>>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+
+    let mut state = state.lock().unwrap();
+    Ok(state.respond(&parts.method, &parts.uri, &parts.headers, &body))
+}
+
+impl MockState {
+    fn new(scheme: &'static str) -> MockState {
+        let mut collections = HashMap::new();
+        for (path, raw) in FIXTURES {
+            let items: Vec<Value> = serde_json::from_str(raw).expect("valid fixture");
+            collections.insert(format!("{}/{}", MOCK_DOMAIN_UUID, path), items);
+            for (_, uuid) in MOCK_SUBDOMAINS {
+                collections.insert(format!("{}/{}", uuid, path), Vec::new());
+            }
+        }
+
+        let dom_uuid = Uuid::parse_str(MOCK_DOMAIN_UUID).unwrap();
+        let mut domains = vec![("Global".to_string(), dom_uuid)];
+        for (name, uuid) in MOCK_SUBDOMAINS {
+            domains.push((name.to_string(), Uuid::parse_str(uuid).unwrap()));
+        }
+
+        MockState {
+            scheme,
+            dom_uuid,
+            domains,
+            server_version: MOCK_SERVER_VERSION.to_string(),
+            access_tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
+            next_id: 1,
+            collections,
+            running_tasks: HashMap::new(),
+            faults: VecDeque::new(),
+            requests: Vec::new(),
+        }
+    }
+
+    fn respond(
+        &mut self,
+        method: &Method,
+        uri: &hyper::Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Response<Body> {
+        let path = uri.path();
+        let query = parse_query(uri.query().unwrap_or(""));
+        self.requests.push(match uri.query() {
+            Some(q) => format!("{} {}?{}", method, path, q),
+            None => format!("{} {}", method, path),
+        });
+
+        if let Some(fault) = self.take_fault(path) {
+            let mut resp = error(fault.status, "Injected fault");
+            if let Some(seconds) = fault.retry_after {
+                resp.headers_mut()
+                    .insert("Retry-After", seconds.to_string().parse().unwrap());
+            }
+            return resp;
+        }
+
+        match (method, path) {
+            (&Method::POST, "/api/fmc_platform/v1/auth/generatetoken") => {
+                return self.generate_token(headers)
+            }
+            (&Method::POST, "/api/fmc_platform/v1/auth/refreshtoken") => {
+                return self.refresh_token(headers)
+            }
+            _ => {}
+        }
+
+        let authorized = headers
+            .get("x-auth-access-token")
+            .and_then(|token| token.to_str().ok())
+            .is_some_and(|token| self.access_tokens.contains_key(token));
+        if !authorized {
+            return error(StatusCode::UNAUTHORIZED, "Access token invalid.");
+        }
+
+        let self_link = match headers.get("host").and_then(|h| h.to_str().ok()) {
+            Some(host) => format!("{}://{}{}", self.scheme, host, path),
+            None => path.to_string(),
+        };
+        match (method, path) {
+            (&Method::GET, "/api/fmc_platform/v1/info/domain") => {
+                return self.info_domain(query, &self_link)
+            }
+            (&Method::GET, "/api/fmc_platform/v1/info/serverversion") => {
+                let item = json!({
+                    "serverVersion": self.server_version,
+                    "vdbVersion": "build 336 ( 2020-05-18 20:46:45 )",
+                    "sruVersion": "2020-05-18-001-vrt",
+                    "geoVersion": "2020-05-12-001",
+                    "type": "ServerVersion",
+                });
+                let mut query = query;
+                query.insert("expanded".to_string(), "true".to_string());
+                return reply(StatusCode::OK, page(&[item], &query, &self_link));
+            }
+            _ => {}
+        }
+
+        // Collections are keyed by "{domain}/{path}", so an unknown
+        // domain UUID finds nothing.
+        let rest = path
+            .strip_prefix("/api/fmc_config/v1/domain/")
+            .or_else(|| path.strip_prefix("/api/fmc_platform/v1/domain/"))
+            .or_else(|| path.strip_prefix("/api/fmc_tid/v1/domain/"));
+        match rest {
+            Some(rest) => self.collection(method, rest, &query, body, &self_link),
+            None => error(StatusCode::NOT_FOUND, "Unknown domain or endpoint"),
+        }
+    }
+
+    fn info_domain(&self, mut query: HashMap<String, String>, self_link: &str) -> Response<Body> {
+        let items: Vec<Value> = self
+            .domains
+            .iter()
+            .map(|(name, uuid)| json!({"uuid": uuid.to_string(), "name": name, "type": "Domain"}))
+            .collect();
+        // Domains have no summary form; FMC always returns them whole.
+        query.insert("expanded".to_string(), "true".to_string());
+        reply(StatusCode::OK, page(&items, &query, self_link))
+    }
+
+    /// Removes one use of the first fault matching `path`.
+    fn take_fault(&mut self, path: &str) -> Option<Fault> {
+        let index = self.faults.iter().position(|fault| match &fault.path {
+            Some(part) => path.contains(part.as_str()),
+            None => true,
+        })?;
+
+        let fault = &mut self.faults[index];
+        fault.times -= 1;
+        let fault = fault.clone();
+        if fault.times == 0 {
+            self.faults.remove(index);
+        }
+        Some(fault)
+    }
+
+    fn generate_token(&mut self, headers: &HeaderMap) -> Response<Body> {
+        let expected = format!(
+            "Basic {}",
+            base64::encode(format!("{}:{}", MOCK_USERNAME, MOCK_PASSWORD))
+        );
+        match headers.get("authorization") {
+            Some(auth) if auth == expected.as_str() => self.issue_tokens(0),
+            _ => error(StatusCode::UNAUTHORIZED, "Invalid username or password."),
+        }
+    }
+
+    fn refresh_token(&mut self, headers: &HeaderMap) -> Response<Body> {
+        let header = |name| headers.get(name).and_then(|h| h.to_str().ok());
+        let (access, refresh) = match (
+            header("x-auth-access-token"),
+            header("x-auth-refresh-token"),
+        ) {
+            (Some(access), Some(refresh)) => (access.to_string(), refresh),
+            _ => return error(StatusCode::UNAUTHORIZED, "Missing token."),
+        };
+
+        if self.refresh_tokens.get(&access).map(String::as_str) != Some(refresh) {
+            return error(StatusCode::UNAUTHORIZED, "Invalid refresh token.");
+        }
+        let refreshes = self.access_tokens[&access];
+        if refreshes >= MAX_REFRESHES {
+            return error(StatusCode::UNAUTHORIZED, "Refresh limit reached.");
+        }
+
+        self.access_tokens.remove(&access);
+        self.refresh_tokens.remove(&access);
+        self.issue_tokens(refreshes + 1)
+    }
+
+    fn issue_tokens(&mut self, refreshes: u8) -> Response<Body> {
+        let access = format!("mock-access-{}", self.next_id());
+        let refresh = format!("mock-refresh-{}", self.next_id());
+        self.access_tokens.insert(access.clone(), refreshes);
+        self.refresh_tokens.insert(access.clone(), refresh.clone());
+
+        let domains: Vec<Value> = self
+            .domains
+            .iter()
+            .map(|(name, uuid)| json!({"name": name, "uuid": uuid.to_string()}))
+            .collect();
+        Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header("X-auth-access-token", access)
+            .header("X-auth-refresh-token", refresh)
+            .header("DOMAIN_UUID", self.dom_uuid.to_string())
+            .header("DOMAINS", Value::Array(domains).to_string())
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn collection(
+        &mut self,
+        method: &Method,
+        rest: &str,
+        query: &HashMap<String, String>,
+        body: &[u8],
+        self_link: &str,
+    ) -> Response<Body> {
+        let (key, id) = match self.route(rest) {
+            Some(route) => route,
+            None => return error(StatusCode::NOT_FOUND, "Unknown endpoint"),
+        };
+
+        match (method, id) {
+            (&Method::GET, None) => {
+                let items = &self.collections[&key];
+                reply(StatusCode::OK, page(items, query, self_link))
+            }
+            (&Method::POST, None) if key.ends_with("/deploymentrequests") => self.deploy(body),
+            (&Method::POST, None) => self.create(&key, query, body),
+            (&Method::GET, Some(id)) if key.ends_with("/taskstatuses") => {
+                self.task_status(&key, &id)
+            }
+            (&Method::GET, Some(id)) => match self.position(&key, &id) {
+                Some(index) => {
+                    let mut item = self.collections[&key][index].clone();
+                    if key.ends_with("/accessrules") {
+                        item["metadata"]["ruleIndex"] = json!(index + 1);
+                    }
+                    reply(StatusCode::OK, item)
+                }
+                None => not_found(&id),
+            },
+            (&Method::PUT, Some(id)) => {
+                let item: Value = match serde_json::from_slice(body) {
+                    Ok(item) => item,
+                    Err(_) => return error(StatusCode::BAD_REQUEST, "Invalid JSON"),
+                };
+                match item["action"].as_str() {
+                    Some(action) if key.ends_with("/ftddevicehapairs") => {
+                        self.ha_action(&key, &id, action)
+                    }
+                    _ => self.replace(&key, id, item),
+                }
+            }
+            (&Method::DELETE, Some(id)) => match self.position(&key, &id) {
+                Some(index) => {
+                    let item = self.collections.get_mut(&key).unwrap().remove(index);
+                    reply(StatusCode::OK, item)
+                }
+                None => not_found(&id),
+            },
+            _ => error(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+        }
+    }
+
+    fn replace(&mut self, key: &str, id: String, mut item: Value) -> Response<Body> {
+        match self.position(key, &id) {
+            Some(index) => {
+                item["id"] = Value::String(id);
+                self.collections.get_mut(key).unwrap()[index] = item.clone();
+                reply(StatusCode::OK, item)
+            }
+            None => not_found(&id),
+        }
+    }
+
+    /// HA pair actions: SWITCH swaps the peers' roles, HABREAK removes
+    /// the pair.
+    fn ha_action(&mut self, key: &str, id: &str, action: &str) -> Response<Body> {
+        let index = match self.position(key, id) {
+            Some(index) => index,
+            None => return not_found(id),
+        };
+        let pairs = self.collections.get_mut(key).unwrap();
+        match action {
+            "SWITCH" => {
+                let metadata = &mut pairs[index]["metadata"];
+                let primary = metadata["primaryStatus"]["currentStatus"].take();
+                let secondary = metadata["secondaryStatus"]["currentStatus"].take();
+                metadata["primaryStatus"]["currentStatus"] = secondary;
+                metadata["secondaryStatus"]["currentStatus"] = primary;
+                reply(StatusCode::OK, pairs[index].clone())
+            }
+            "HABREAK" => reply(StatusCode::OK, pairs.remove(index)),
+            _ => error(StatusCode::BAD_REQUEST, "Unknown HA action"),
+        }
+    }
+
+    /// Starts a deployment task for the requested devices. It reports
+    /// Deploying on its first poll and Deployed on every one after.
+    fn deploy(&mut self, body: &[u8]) -> Response<Body> {
+        let mut request: Value = match serde_json::from_slice(body) {
+            Ok(request) => request,
+            Err(_) => return error(StatusCode::BAD_REQUEST, "Invalid JSON"),
+        };
+        let devices = match request["deviceList"].as_array() {
+            Some(devices) if !devices.is_empty() => devices.clone(),
+            _ => return error(StatusCode::BAD_REQUEST, "deviceList is required"),
+        };
+
+        let id = self.next_id().to_string();
+        let sub_tasks: Vec<Value> = devices
+            .iter()
+            .map(
+                |device| json!({"target": {"id": device, "type": "Device"}, "status": "Deploying"}),
+            )
+            .collect();
+        let task = json!({
+            "id": id,
+            "type": "TaskStatus",
+            "taskType": "DEVICE_DEPLOYMENT",
+            "status": "Deploying",
+            "subTasks": sub_tasks,
+        });
+        let key = format!("{}/taskstatuses", self.dom_uuid);
+        self.collections.get_mut(&key).unwrap().push(task);
+        self.running_tasks.insert(id.clone(), 1);
+
+        request["metadata"] = json!({"task": {"id": id, "taskType": "DEVICE_DEPLOYMENT"}});
+        reply(StatusCode::ACCEPTED, request)
+    }
+
+    /// A task as it stands, then moves a running deployment one poll on.
+    fn task_status(&mut self, key: &str, id: &str) -> Response<Body> {
+        let index = match self.position(key, id) {
+            Some(index) => index,
+            None => return not_found(id),
+        };
+        let task = &mut self.collections.get_mut(key).unwrap()[index];
+        let status = task.clone();
+
+        if let Some(polls) = self.running_tasks.get_mut(id) {
+            *polls -= 1;
+            if *polls == 0 {
+                self.running_tasks.remove(id);
+                task["status"] = json!("Deployed");
+                for sub_task in task["subTasks"].as_array_mut().into_iter().flatten() {
+                    sub_task["status"] = json!("Deployed");
+                }
+            }
+        }
+        reply(StatusCode::OK, status)
+    }
+
+    /// Splits "object/hosts/{id}" into the collection key and the ID.
+    fn route(&self, rest: &str) -> Option<(String, Option<String>)> {
+        if self.collections.contains_key(rest) {
+            return Some((rest.to_string(), None));
+        }
+        let (key, id) = rest.split_at(rest.rfind('/')?);
+        if self.collections.contains_key(key) && id.len() > 1 {
+            return Some((key.to_string(), Some(id[1..].to_string())));
+        }
+        None
+    }
+
+    fn position(&self, key: &str, id: &str) -> Option<usize> {
+        self.collections[key]
+            .iter()
+            .position(|item| item["id"].as_str() == Some(id))
+    }
+
+    fn create(
+        &mut self,
+        key: &str,
+        query: &HashMap<String, String>,
+        body: &[u8],
+    ) -> Response<Body> {
+        let body: Value = match serde_json::from_slice(body) {
+            Ok(body) => body,
+            Err(_) => return error(StatusCode::BAD_REQUEST, "Invalid JSON"),
+        };
+
+        let bulk = query.get("bulk").map(String::as_str) == Some("true");
+        let (items, bulk) = match body {
+            Value::Array(items) if bulk => (items, true),
+            Value::Array(_) => return error(StatusCode::BAD_REQUEST, "Bulk requires bulk=true"),
+            item => (vec![item], false),
+        };
+
+        let mut created = Vec::with_capacity(items.len());
+        for mut item in items {
+            item["id"] = Value::String(format!("00000000-0000-0000-0000-{:012}", self.next_id()));
+            created.push(item);
+        }
+        let items = self.collections.get_mut(key).unwrap();
+        let at = insert_position(query, items.len());
+        items.splice(at..at, created.iter().cloned());
+
+        match bulk {
+            true => reply(StatusCode::CREATED, json!({ "items": created })),
+            false => reply(StatusCode::CREATED, created.remove(0)),
+        }
+    }
+}
+
+/// Where new items go: after the 1-based insertAfter index, before the
+/// insertBefore index, or at the end.
+fn insert_position(query: &HashMap<String, String>, len: usize) -> usize {
+    let index = |name: &str| query.get(name).and_then(|i| i.parse::<usize>().ok());
+    match (index("insertAfter"), index("insertBefore")) {
+        (Some(after), _) => after.min(len),
+        (None, Some(before)) => before.saturating_sub(1).min(len),
+        (None, None) => len,
+    }
+}
+
+/// One page of `items`, honouring offset, limit, expanded and name filters.
+fn page(items: &[Value], query: &HashMap<String, String>, self_link: &str) -> Value {
+    let offset: usize = query
+        .get("offset")
+        .and_then(|o| o.parse().ok())
+        .unwrap_or(0);
+    let limit: usize = query
+        .get("limit")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(DEFAULT_LIMIT)
+        .max(1);
+    let expanded = query.get("expanded").map(String::as_str) == Some("true");
+
+    let matching: Vec<&Value> = items
+        .iter()
+        .filter(|item| matches_filter(item, query.get("filter")))
+        .collect();
+    let count = matching.len();
+
+    let page: Vec<Value> = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|item| match expanded {
+            true => item.clone(),
+            false => json!({
+                "id": item["id"],
+                "type": item["type"],
+                "name": item["name"],
+                "links": {"self": format!("{}/{}", self_link, item["id"].as_str().unwrap_or(""))},
+            }),
+        })
+        .collect();
+
+    let mut reply = json!({
+        "links": {"self": format!("{}?offset={}&limit={}", self_link, offset, limit)},
+        "paging": {
+            "offset": offset,
+            "limit": limit,
+            "count": count,
+            "pages": count.div_ceil(limit),
+        },
+    });
+    // Like FMC, an empty page has no items key at all.
+    if !page.is_empty() {
+        reply["items"] = Value::Array(page);
+    }
+    reply
+}
+
+/// Supports name:{x} (exact) and nameOrValue:{x} (substring) clauses, and
+/// the audit record clauses: startTime/endTime (epoch seconds, inclusive),
+/// username, subsystem and source.
+fn matches_filter(item: &Value, filter: Option<&String>) -> bool {
+    let filter = match filter {
+        Some(filter) => filter,
+        None => return true,
+    };
+    let field = |name: &str| item[name].as_str().unwrap_or("");
+
+    filter
+        .split(';')
+        .all(|clause| match clause.split_once(':') {
+            Some(("name", name)) => field("name") == name,
+            Some(("nameOrValue", text)) => {
+                field("name").contains(text) || field("value").contains(text)
+            }
+            Some(("startTime", start)) => item["time"].as_i64() >= start.parse().ok(),
+            Some(("endTime", end)) => match end.parse::<i64>() {
+                Ok(end) => item["time"].as_i64().is_some_and(|time| time <= end),
+                Err(_) => false,
+            },
+            Some((key @ ("username" | "subsystem" | "source"), text)) => field(key) == text,
+            _ => true,
+        })
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = text
+            .get(i + 1..i + 3)
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn reply(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// An error in FMC's {"error": {"messages": [...]}} shape.
+fn error(status: StatusCode, description: &str) -> Response<Body> {
+    let body = json!({
+        "error": {
+            "category": "FRAMEWORK",
+            "messages": [{"description": description}],
+            "severity": "ERROR",
+        }
+    });
+    reply(status, body)
+}
+
+fn not_found(id: &str) -> Response<Body> {
+    error(StatusCode::NOT_FOUND, &format!("UUID {} not found", id))
+}
+
+/// A Device reference with only an ID, the way tests build them.
+pub fn device_ref(id: &str) -> Reference {
+    Reference {
+        id: id.to_string(),
+        _type: Some("Device".to_string()),
+        name: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::objects::objects_schema::Host;
+    use super::super::super::ExponentialBackoff;
+    use super::super::super::FmcError;
+    use super::*;
+
+    #[tokio::test]
+    async fn fn_login_and_paginate() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        client.login().await?;
+        assert_eq!(
+            client.session().await.dom_uuid().unwrap().to_string(),
+            MOCK_DOMAIN_UUID
+        );
+
+        let devices = client
+            .devices()
+            .paginate()
+            .page_size(2)
+            .expanded(true)
+            .collect_all()
+            .await?;
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[2].name, "ftd-dc-1");
+
+        let requests = mock.requests();
+        assert!(requests[0].ends_with("/auth/generatetoken"));
+        assert!(requests[1].ends_with("/info/serverversion"));
+        assert!(requests[2].ends_with("devicerecords?offset=0&limit=2&expanded=true"));
+        assert!(requests[3].ends_with("devicerecords?offset=2&limit=2&expanded=true"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_object_crud() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let hosts = client.objects().hosts();
+
+        let created = hosts.create(&Host::new("app-01", "10.0.0.20")).await?;
+        let id = created.id.clone().unwrap();
+        assert_eq!(hosts.get(&id).await?.value, "10.0.0.20");
+        assert_eq!(hosts.find("web-02").await?.unwrap().value, "10.0.0.11");
+
+        hosts.delete(&id).await?;
+        let err = hosts.get(&id).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_retry_on_429() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock
+            .client()
+            .await
+            .retry_policy(ExponentialBackoff::new().max_retries(2));
+        client.login().await?;
+
+        mock.fail(
+            Fault::new(429)
+                .path("devicerecords")
+                .retry_after(0)
+                .times(2),
+        );
+        assert_eq!(client.devices().list().await?.items.len(), 3);
+        assert_eq!(mock.requests().len(), 5);
+
+        mock.fail(
+            Fault::new(429)
+                .path("devicerecords")
+                .retry_after(0)
+                .times(3),
+        );
+        let err = client.devices().list().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(mock.requests().len(), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_error_injection() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        client.login().await?;
+
+        mock.fail(Fault::new(500).path("devicerecords"));
+        mock.fail(Fault::new(503).path("object").retry_after(0).times(4));
+        let err = client.devices().list().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::INTERNAL_SERVER_ERROR));
+        let err = client.objects().hosts().list().page(0).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(mock.requests().len(), 7);
+        assert_eq!(client.devices().list().await?.items.len(), 3);
+
+        mock.expire_tokens();
+        let err = client.devices().list().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+
+        let bad = FmcClient::new(&mock.url(), MOCK_USERNAME, "wrong").await?;
+        match bad.login().await {
+            Err(FmcError::Status {
+                status,
+                body: Some(body),
+            }) => {
+                assert_eq!(status, StatusCode::UNAUTHORIZED);
+                assert_eq!(body.description(), "Invalid username or password.");
+            }
+            other => panic!("expected 401, got {:?}", other),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use super::{StatusCode, Uuid, Value};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// A mock FMC listening on 127.0.0.1 over HTTP or HTTPS. Stops when dropped.
+pub struct MockFmc {
+    pub(super) addr: SocketAddr,
+    pub(super) tls: bool,
+    pub(super) state: Arc<Mutex<MockState>>,
+    pub(super) shutdown: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct MockState {
+    pub(super) scheme: &'static str,
+    pub(super) dom_uuid: Uuid,
+    /// Global first, then MOCK_SUBDOMAINS, as (name, UUID).
+    pub(super) domains: Vec<(String, Uuid)>,
+    /// Live access tokens and how often their chain has been refreshed.
+    pub(super) access_tokens: HashMap<String, u8>,
+    /// Refresh token for each live access token.
+    pub(super) refresh_tokens: HashMap<String, String>,
+    pub(super) server_version: String,
+    pub(super) next_id: u64,
+    /// Keyed by "{domain UUID}/{path}", e.g. "{uuid}/object/hosts".
+    pub(super) collections: HashMap<String, Vec<Value>>,
+    /// Deployment tasks started through the mock, and how many more
+    /// polls each reports Deploying before it finishes.
+    pub(super) running_tasks: HashMap<String, usize>,
+    pub(super) faults: VecDeque<Fault>,
+    pub(super) requests: Vec<String>,
+}
+
+/// A canned error reply, e.g. Fault::new(429).retry_after(1).times(2).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fault {
+    pub(super) status: StatusCode,
+    pub(super) path: Option<String>,
+    pub(super) retry_after: Option<u64>,
+    pub(super) times: usize,
+}
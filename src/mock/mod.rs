@@ -0,0 +1,75 @@
+//! An in-process stand-in for an FMC, for tests that must run without an
+//! appliance. Compiled for this crate's tests and behind the `mock` feature.
+
+use super::client::client_schema::FmcClient;
+use super::json::json_schema::core::Reference;
+use super::tls::tls_schema::{FmcStream, TlsConfig};
+use super::Uuid;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+
+mod mock_methods;
+pub mod mock_schema;
+
+pub use mock_methods::device_ref;
+
+pub const MOCK_USERNAME: &str = "apiuser";
+pub const MOCK_PASSWORD: &str = "mock-password";
+/// FMC's Global domain UUID, as reported in the DOMAIN_UUID header.
+pub const MOCK_DOMAIN_UUID: &str = "e276abec-e0f2-11e3-8169-6d9ed49b625f";
+/// What /info/serverversion reports until MockFmc::set_version().
+pub const MOCK_SERVER_VERSION: &str = "6.6.0 (build 90)";
+/// Leaf domains under Global as (name, UUID). Their collections start empty.
+pub const MOCK_SUBDOMAINS: &[(&str, &str)] = &[
+    ("Global/Tenant-A", "7d3f5a10-2b1e-4c8f-9a61-0c2d4e6f8a01"),
+    ("Global/Tenant-B", "7d3f5a10-2b1e-4c8f-9a61-0c2d4e6f8a02"),
+];
+
+/// CA that issued the mock's HTTPS certificate.
+pub const MOCK_CA_PEM: &[u8] = include_bytes!("fixtures/mock-ca.pem");
+/// The mock's HTTPS certificate, for 127.0.0.1 and localhost.
+pub const MOCK_CERT_PEM: &[u8] = include_bytes!("fixtures/mock-cert.pem");
+const MOCK_KEY_PEM: &[u8] = include_bytes!("fixtures/mock-key.pem");
+
+/// Same page size FMC uses when a request gives no limit.
+const DEFAULT_LIMIT: usize = 25;
+
+/// Collections loaded into Global at start, keyed by their path under the domain.
+const FIXTURES: &[(&str, &str)] = &[
+    (
+        "devices/devicerecords",
+        include_str!("fixtures/devicerecords.json"),
+    ),
+    (
+        "deployment/deployabledevices",
+        include_str!("fixtures/deployabledevices.json"),
+    ),
+    ("deployment/deploymentrequests", "[]"),
+    ("taskstatuses", "[]"),
+    ("deviceclusters/ftddevicecluster", "[]"),
+    ("devicegroups/devicegrouprecords", "[]"),
+    (
+        "devicehapairs/ftddevicehapairs",
+        include_str!("fixtures/hapairs.json"),
+    ),
+    (
+        "devicehapairs/ftddevicehapairs/00505686-7a2e-0ed3-0000-000000000401/monitoredinterfaces",
+        include_str!("fixtures/monitoredinterfaces.json"),
+    ),
+    ("object/hosts", include_str!("fixtures/hosts.json")),
+    ("object/networks", include_str!("fixtures/networks.json")),
+    (
+        "audit/auditrecords",
+        include_str!("fixtures/auditrecords.json"),
+    ),
+    ("taxiiconfig/discoveryinfo", "[]"),
+    ("taxiiconfig/collections", "[]"),
+    ("tid/source", "[]"),
+    ("tid/indicator", include_str!("fixtures/tidindicators.json")),
+    (
+        "tid/observable",
+        include_str!("fixtures/tidobservables.json"),
+    ),
+    ("tid/incident", include_str!("fixtures/tidincidents.json")),
+    ("tid/element", include_str!("fixtures/tidelements.json")),
+];
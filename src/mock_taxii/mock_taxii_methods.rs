@@ -0,0 +1,678 @@
+use super::mock_taxii_schema::{MockCollection, MockTaxii, TaxiiProbe, TaxiiState};
+use super::{decode, json, Body, Method, Request, Response, StatusCode, Value};
+use super::{Deserialize, FmcError, TlsConfig};
+use super::{TaxiiCollection, TaxiiDiscovery, TaxiiVersion};
+use super::{API_ROOT, TAXII1_HEADERS, TAXII1_NAMESPACE, TAXII1_SERVICES, TAXII2_MEDIA_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Client, Server};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+impl MockTaxii {
+    /// Starts a server on a free local port with no collections.
+    pub async fn start() -> MockTaxii {
+        let state = Arc::new(Mutex::new(TaxiiState {
+            base: String::new(),
+            collections: Vec::new(),
+            authorization: None,
+            next_id: 0,
+            requests: Vec::new(),
+        }));
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+
+        let (shutdown, stop) = oneshot::channel::<()>();
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        state.lock().unwrap().base = format!("http://{}", addr);
+        tokio::spawn(server.with_graceful_shutdown(async {
+            stop.await.ok();
+        }));
+
+        MockTaxii {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Base URL, e.g. "http://127.0.0.1:53212".
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Where a client starts: the 2.1 discovery endpoint or the 1.1
+    /// discovery service.
+    pub fn discovery_url(&self, version: &TaxiiVersion) -> String {
+        match version {
+            TaxiiVersion::V11 => format!("{}{}", self.url(), TAXII1_SERVICES[0].1),
+            _ => format!("{}/taxii2/", self.url()),
+        }
+    }
+
+    /// Adds a readable collection holding these STIX 2.1 objects. Over
+    /// TAXII 1.1 it is named `id`.
+    pub fn collection(&self, id: &str, title: &str, objects: Vec<Value>) {
+        self.state.lock().unwrap().collections.push(MockCollection {
+            id: id.to_string(),
+            title: title.to_string(),
+            objects,
+        });
+    }
+
+    /// Rejects requests without these HTTP Basic credentials.
+    pub fn require_credentials(&self, username: &str, password: &str) {
+        let basic = base64::encode(format!("{}:{}", username, password));
+        self.state.lock().unwrap().authorization = Some(format!("Basic {}", basic));
+    }
+
+    /// Every request seen so far, as "METHOD /path".
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockTaxii {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<TaxiiState>>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error> {
+    let (parts, body) = req.into_parts();
+    let body = hyper::body::to_bytes(body).await?;
+    let body = String::from_utf8_lossy(&body);
+
+    let mut state = state.lock().unwrap();
+    state
+        .requests
+        .push(format!("{} {}", parts.method, parts.uri.path()));
+
+    let authorized = match &state.authorization {
+        Some(expected) => {
+            parts.headers.get("authorization").map(|h| h.as_bytes()) == Some(expected.as_bytes())
+        }
+        None => true,
+    };
+    let path = parts.uri.path();
+    Ok(match parts.method {
+        Method::POST if TAXII1_SERVICES.iter().any(|(_, address)| *address == path) => {
+            let request_id = xml::attribute(&body, "message_id").unwrap_or_default();
+            let message = match authorized {
+                true => state.taxii1(path, &body),
+                false => status_message("UNAUTHORIZED"),
+            };
+            taxii1_reply(&message, &state.next_message_id(), &request_id)
+        }
+        Method::GET if !authorized => taxii2_reply(
+            StatusCode::UNAUTHORIZED,
+            json!({"title": "Unauthorized", "http_status": "401"}),
+        ),
+        Method::GET => state.taxii2(path),
+        _ => taxii2_reply(
+            StatusCode::METHOD_NOT_ALLOWED,
+            json!({"title": "Method not allowed", "http_status": "405"}),
+        ),
+    })
+}
+
+impl TaxiiState {
+    fn next_message_id(&mut self) -> String {
+        self.next_id += 1;
+        self.next_id.to_string()
+    }
+
+    fn taxii2(&self, path: &str) -> Response<Body> {
+        let not_found = || {
+            taxii2_reply(
+                StatusCode::NOT_FOUND,
+                json!({"title": "Not found", "http_status": "404"}),
+            )
+        };
+        let root = format!("{}{}", self.base, API_ROOT);
+        if path == "/taxii2/" {
+            let body = json!({"title": "Mock TAXII", "default": root, "api_roots": [root]});
+            return taxii2_reply(StatusCode::OK, body);
+        }
+
+        let rest = match path.strip_prefix(API_ROOT) {
+            Some(rest) => rest,
+            None => return not_found(),
+        };
+        let parts: Vec<&str> = rest.split('/').filter(|p| !p.is_empty()).collect();
+        let find = |id: &str| self.collections.iter().find(|c| c.id == id);
+        match parts.as_slice() {
+            [] => taxii2_reply(
+                StatusCode::OK,
+                json!({"title": "Mock feeds", "versions": [TAXII2_MEDIA_TYPE]}),
+            ),
+            ["collections"] => {
+                let collections: Vec<Value> = self.collections.iter().map(describe).collect();
+                taxii2_reply(StatusCode::OK, json!({ "collections": collections }))
+            }
+            ["collections", id] => match find(id) {
+                Some(collection) => taxii2_reply(StatusCode::OK, describe(collection)),
+                None => not_found(),
+            },
+            ["collections", id, "objects"] => match find(id) {
+                Some(collection) => taxii2_reply(
+                    StatusCode::OK,
+                    json!({"more": false, "objects": collection.objects}),
+                ),
+                None => not_found(),
+            },
+            _ => not_found(),
+        }
+    }
+
+    /// The reply message for a TAXII 1.1 request to the service at `path`.
+    fn taxii1(&self, path: &str, body: &str) -> String {
+        let service = TAXII1_SERVICES
+            .iter()
+            .find(|(_, address)| *address == path)
+            .map(|(service, _)| *service);
+        match service {
+            Some("DISCOVERY") => {
+                let instances: String = TAXII1_SERVICES
+                    .iter()
+                    .map(|(service, address)| {
+                        format!(
+                            r#"<taxii_11:Service_Instance service_type="{}" service_version="urn:taxii.mitre.org:services:1.1" available="true">{}</taxii_11:Service_Instance>"#,
+                            service,
+                            self.bindings(address)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "<taxii_11:Discovery_Response>{}</taxii_11:Discovery_Response>",
+                    instances
+                )
+            }
+            Some("COLLECTION_MANAGEMENT") => {
+                let collections: String = self
+                    .collections
+                    .iter()
+                    .map(|collection| {
+                        format!(
+                            r#"<taxii_11:Collection collection_name="{}" collection_type="DATA_FEED" available="true"><taxii_11:Description>{}</taxii_11:Description><taxii_11:Polling_Service>{}</taxii_11:Polling_Service></taxii_11:Collection>"#,
+                            escape(&collection.id),
+                            escape(&collection.title),
+                            self.bindings(TAXII1_SERVICES[2].1)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "<taxii_11:Collection_Information_Response>{}</taxii_11:Collection_Information_Response>",
+                    collections
+                )
+            }
+            _ => self.poll(body),
+        }
+    }
+
+    /// Poll_Response with one STIX 1.x package per object.
+    fn poll(&self, body: &str) -> String {
+        let name = xml::elements(body, "Poll_Request")
+            .into_iter()
+            .next()
+            .and_then(|(tag, _)| xml::attribute(tag, "collection_name"));
+        let collection = match name.and_then(|name| self.collections.iter().find(|c| c.id == name))
+        {
+            Some(collection) => collection,
+            None => return status_message("NOT_FOUND"),
+        };
+
+        let blocks: String = collection
+            .objects
+            .iter()
+            .map(|object| {
+                let title = object["name"]
+                    .as_str()
+                    .or_else(|| object["pattern"].as_str())
+                    .unwrap_or("");
+                let package = format!(
+                    r#"<stix:STIX_Package xmlns:stix="http://stix.mitre.org/stix-1" id="{}" version="1.2"><stix:STIX_Header><stix:Title>{}</stix:Title></stix:STIX_Header></stix:STIX_Package>"#,
+                    escape(object["id"].as_str().unwrap_or("")),
+                    escape(title)
+                );
+                format!(
+                    r#"<taxii_11:Content_Block><taxii_11:Content_Binding binding_id="urn:stix.mitre.org:xml:1.1.1"/><taxii_11:Content>{}</taxii_11:Content></taxii_11:Content_Block>"#,
+                    package
+                )
+            })
+            .collect();
+        format!(
+            r#"<taxii_11:Poll_Response collection_name="{}" more="false" result_part_number="1"><taxii_11:Record_Count partial_count="false">{}</taxii_11:Record_Count>{}</taxii_11:Poll_Response>"#,
+            escape(&collection.id),
+            collection.objects.len(),
+            blocks
+        )
+    }
+
+    fn bindings(&self, address: &str) -> String {
+        format!(
+            "<taxii_11:Protocol_Binding>urn:taxii.mitre.org:protocol:http:1.0</taxii_11:Protocol_Binding>\
+             <taxii_11:Address>{}{}</taxii_11:Address>\
+             <taxii_11:Message_Binding>urn:taxii.mitre.org:message:xml:1.1</taxii_11:Message_Binding>",
+            self.base, address
+        )
+    }
+}
+
+/// A 2.1 collection resource.
+fn describe(collection: &MockCollection) -> Value {
+    json!({
+        "id": collection.id,
+        "title": collection.title,
+        "can_read": true,
+        "can_write": false,
+        "media_types": ["application/stix+json;version=2.1"],
+    })
+}
+
+fn status_message(status_type: &str) -> String {
+    format!(
+        r#"<taxii_11:Status_Message status_type="{}"/>"#,
+        status_type
+    )
+}
+
+/// Adds the namespace and message IDs to `message`'s root element.
+fn taxii1_reply(message: &str, message_id: &str, in_response_to: &str) -> Response<Body> {
+    let (open, rest) = message.split_at(message.find(&[' ', '>', '/'][..]).unwrap_or(0));
+    let body = format!(
+        r#"{} xmlns:taxii_11="{}" message_id="{}" in_response_to="{}"{}"#,
+        open,
+        TAXII1_NAMESPACE,
+        message_id,
+        escape(in_response_to),
+        rest
+    );
+    TAXII1_HEADERS
+        .iter()
+        .fold(Response::builder(), |resp, (name, value)| {
+            resp.header(*name, *value)
+        })
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn taxii2_reply(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", TAXII2_MEDIA_TYPE)
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// TAXII 2.1 discovery reply.
+#[derive(Deserialize)]
+struct ApiRoots {
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    api_roots: Vec<String>,
+}
+
+/// TAXII 2.1 reply to {api root}/collections/.
+#[derive(Deserialize)]
+struct Collections {
+    #[serde(default)]
+    collections: Vec<CollectionInfo>,
+}
+
+#[derive(Deserialize)]
+struct CollectionInfo {
+    id: String,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    can_read: bool,
+}
+
+impl TaxiiProbe {
+    /// Verifies the server against the system roots.
+    pub fn new(discovery: &TaxiiDiscovery) -> Result<TaxiiProbe, FmcError> {
+        TaxiiProbe::with_tls(discovery, TlsConfig::new())
+    }
+
+    pub fn with_tls(discovery: &TaxiiDiscovery, tls: TlsConfig) -> Result<TaxiiProbe, FmcError> {
+        Ok(TaxiiProbe {
+            http: Client::builder().build::<_, Body>(tls.connector()?),
+            discovery: discovery.clone(),
+        })
+    }
+
+    /// Readable collections the server offers, ready to save with
+    /// client.taxii().collections().create() or onboard().
+    pub async fn collections(&self) -> Result<Vec<TaxiiCollection>, FmcError> {
+        match &self.discovery.version {
+            TaxiiVersion::V11 => self.collections_v11().await,
+            TaxiiVersion::V21 => self.collections_v21().await,
+            TaxiiVersion::Other(version) => Err(FmcError::Taxii(format!(
+                "unsupported TAXII version {:?}",
+                version
+            ))),
+        }
+    }
+
+    /// Discovery, then the default (or first) API root's collection list.
+    async fn collections_v21(&self) -> Result<Vec<TaxiiCollection>, FmcError> {
+        let url = &self.discovery.url;
+        let ApiRoots { default, api_roots } = decode(&self.send(Method::GET, url, None).await?)?;
+        let root = match default.or_else(|| api_roots.into_iter().next()) {
+            Some(root) => absolute(url, &root),
+            None => return Err(FmcError::Taxii("no API roots".to_string())),
+        };
+        let root = format!("{}/", root.trim_end_matches('/'));
+
+        let reply: Collections = decode(
+            &self
+                .send(Method::GET, &format!("{}collections/", root), None)
+                .await?,
+        )?;
+        Ok(reply
+            .collections
+            .into_iter()
+            .filter(|info| info.can_read)
+            .map(|info| {
+                let url = format!("{}collections/{}/objects/", root, info.id);
+                let mut collection = TaxiiCollection::new(&self.discovery, &info.id, &url);
+                collection.name = info.title;
+                collection.description = info.description;
+                collection
+            })
+            .collect())
+    }
+
+    /// Finds the collection management service, then lists its
+    /// collections with their first poll address.
+    async fn collections_v11(&self) -> Result<Vec<TaxiiCollection>, FmcError> {
+        let reply = self
+            .send(
+                Method::POST,
+                &self.discovery.url,
+                Some(request_message("Discovery_Request")),
+            )
+            .await?;
+        let reply = reply_message(&reply, "Discovery_Response")?;
+        let management = xml::elements(reply, "Service_Instance")
+            .into_iter()
+            .find(|(tag, _)| {
+                xml::attribute(tag, "service_type").as_deref() == Some("COLLECTION_MANAGEMENT")
+            })
+            .and_then(|(_, inner)| xml::text(inner, "Address"))
+            .ok_or_else(|| FmcError::Taxii("no collection management service".to_string()))?;
+
+        let reply = self
+            .send(
+                Method::POST,
+                &absolute(&self.discovery.url, &management),
+                Some(request_message("Collection_Information_Request")),
+            )
+            .await?;
+        let reply = reply_message(&reply, "Collection_Information_Response")?;
+        let collections = xml::elements(reply, "Collection")
+            .into_iter()
+            .filter(|(tag, _)| xml::attribute(tag, "available").as_deref() != Some("false"))
+            .filter_map(|(tag, inner)| {
+                let name = xml::attribute(tag, "collection_name")?;
+                let poll = xml::elements(inner, "Polling_Service")
+                    .into_iter()
+                    .find_map(|(_, service)| xml::text(service, "Address"))?;
+                let url = absolute(&self.discovery.url, &poll);
+                let mut collection = TaxiiCollection::new(&self.discovery, &name, &url);
+                collection.description = xml::text(inner, "Description");
+                Some(collection)
+            });
+        Ok(collections.collect())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<String, FmcError> {
+        let mut req = Request::builder().method(method).uri(url);
+        req = match self.discovery.version {
+            TaxiiVersion::V11 => TAXII1_HEADERS
+                .iter()
+                .fold(req, |req, (name, value)| req.header(*name, *value))
+                .header("Accept", "application/xml"),
+            _ => req.header("Accept", TAXII2_MEDIA_TYPE),
+        };
+        if let (Some(username), Some(password)) =
+            (&self.discovery.username, &self.discovery.password)
+        {
+            let basic = base64::encode(format!("{}:{}", username, password));
+            req = req.header("Authorization", format!("Basic {}", basic));
+        }
+
+        let resp: Response<Body> = self
+            .http
+            .request(req.body(body.map(Body::from).unwrap_or_else(Body::empty))?)
+            .await?;
+        let status = resp.status();
+        let bytes = hyper::body::to_bytes(resp.into_body()).await?;
+        if !status.is_success() {
+            return Err(FmcError::Status { status, body: None });
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// An empty TAXII 1.1 request message such as Discovery_Request.
+fn request_message(message: &str) -> String {
+    format!(
+        r#"<taxii_11:{} xmlns:taxii_11="{}" message_id="{}"/>"#,
+        message,
+        TAXII1_NAMESPACE,
+        rand::random::<u32>()
+    )
+}
+
+/// The body of the expected reply message, or the server's Status_Message
+/// as an error.
+fn reply_message<'x>(xml: &'x str, message: &str) -> Result<&'x str, FmcError> {
+    if let Some((_, inner)) = xml::elements(xml, message).into_iter().next() {
+        return Ok(inner);
+    }
+    let status = xml::elements(xml, "Status_Message")
+        .into_iter()
+        .next()
+        .and_then(|(tag, _)| xml::attribute(tag, "status_type"));
+    Err(FmcError::Taxii(match status {
+        Some(status) => format!("{} instead of {}", status, message),
+        None => format!("no {}", message),
+    }))
+}
+
+/// Resolves a server-relative URL ("/feeds/") against `base`.
+fn absolute(base: &str, url: &str) -> String {
+    if url.contains("://") {
+        return url.to_string();
+    }
+    let origin = match base.find("://") {
+        Some(scheme) => match base[scheme + 3..].find('/') {
+            Some(path) => &base[..scheme + 3 + path],
+            None => base,
+        },
+        None => base,
+    };
+    format!("{}/{}", origin, url.trim_start_matches('/'))
+}
+
+/// Just enough XML reading for the TAXII 1.1 messages MockTaxii and
+/// TaxiiProbe exchange: elements are matched by local name, whatever their
+/// namespace prefix, and must not nest in themselves.
+mod xml {
+    /// (attributes of the opening tag, inner XML) of each `name` element.
+    pub(super) fn elements<'x>(xml: &'x str, name: &str) -> Vec<(&'x str, &'x str)> {
+        let mut found = Vec::new();
+        let mut rest = xml;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            let end = match rest.find('>') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = &rest[..end];
+            rest = &rest[end + 1..];
+
+            let tag_name = tag
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or("");
+            if tag_name.rsplit(':').next() != Some(name) {
+                continue;
+            }
+            if let Some(tag) = tag.strip_suffix('/') {
+                found.push((tag, ""));
+                continue;
+            }
+            let close = format!("</{}>", tag_name);
+            let inner_end = rest.find(&close).unwrap_or(rest.len());
+            found.push((tag, &rest[..inner_end]));
+            rest = &rest[(inner_end + close.len()).min(rest.len())..];
+        }
+        found
+    }
+
+    pub(super) fn attribute(tag: &str, name: &str) -> Option<String> {
+        let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+        let len = tag[start..].find('"')?;
+        Some(unescape(&tag[start..start + len]))
+    }
+
+    /// Text of the first `name` element in `xml`.
+    pub(super) fn text(xml: &str, name: &str) -> Option<String> {
+        let (_, inner) = elements(xml, name).into_iter().next()?;
+        Some(unescape(inner.trim()))
+    }
+
+    fn unescape(text: &str) -> String {
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn send(req: Request<Body>) -> (StatusCode, String) {
+        let resp = Client::new().request(req).await.unwrap();
+        let status = resp.status();
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[test]
+    fn fn_read_xml() {
+        let reply = r#"<taxii_11:Collection_Information_Response message_id="7" in_response_to="1">
+            <taxii_11:Collection collection_name="ips &amp; domains" available="true">
+              <taxii_11:Description>Bad things</taxii_11:Description>
+              <taxii_11:Polling_Service>
+                <taxii_11:Protocol_Binding>urn:taxii.mitre.org:protocol:http:1.0</taxii_11:Protocol_Binding>
+                <taxii_11:Address>/taxii-poll-service</taxii_11:Address>
+              </taxii_11:Polling_Service>
+            </taxii_11:Collection>
+            <taxii_11:Collection collection_name="retired" available="false"/>
+          </taxii_11:Collection_Information_Response>"#;
+
+        let reply = reply_message(reply, "Collection_Information_Response").unwrap();
+        let collections = xml::elements(reply, "Collection");
+        assert_eq!(collections.len(), 2);
+        assert_eq!(
+            xml::attribute(collections[0].0, "collection_name").as_deref(),
+            Some("ips & domains")
+        );
+        assert_eq!(
+            xml::text(collections[0].1, "Address").as_deref(),
+            Some("/taxii-poll-service")
+        );
+        assert_eq!(
+            xml::attribute(collections[1].0, "available").as_deref(),
+            Some("false")
+        );
+
+        let denied = r#"<taxii_11:Status_Message status_type="UNAUTHORIZED" message_id="8"/>"#;
+        assert!(matches!(
+            reply_message(denied, "Discovery_Response"),
+            Err(FmcError::Taxii(_))
+        ));
+        assert_eq!(
+            absolute("http://127.0.0.1:9000/taxii2/", "/feeds/"),
+            "http://127.0.0.1:9000/feeds/"
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_serve_content() {
+        let taxii = MockTaxii::start().await;
+        let objects = vec![
+            json!({"type": "indicator", "id": "indicator--1", "name": "bad & worse", "pattern": "[ipv4-addr:value = '203.0.113.7']"}),
+            json!({"type": "indicator", "id": "indicator--2", "pattern": "[domain-name:value = 'bad.example.com']"}),
+        ];
+        taxii.collection("pipeline-ips", "Pipeline IPs", objects);
+
+        let (status, body) = send(
+            Request::get(format!(
+                "{}/feeds/collections/pipeline-ips/objects/",
+                taxii.url()
+            ))
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let envelope: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(envelope["objects"].as_array().unwrap().len(), 2);
+
+        let poll = format!(
+            r#"<taxii_11:Poll_Request xmlns:taxii_11="{}" message_id="42" collection_name="pipeline-ips"/>"#,
+            TAXII1_NAMESPACE
+        );
+        let (_, body) = send(
+            Request::post(format!("{}/taxii-poll-service", taxii.url()))
+                .body(Body::from(poll))
+                .unwrap(),
+        )
+        .await;
+        let (tag, inner) = xml::elements(&body, "Poll_Response").remove(0);
+        assert_eq!(xml::attribute(tag, "in_response_to").as_deref(), Some("42"));
+        assert_eq!(xml::elements(inner, "Content_Block").len(), 2);
+        assert_eq!(xml::text(inner, "Title").as_deref(), Some("bad & worse"));
+
+        taxii.require_credentials("feeduser", "feedpass");
+        let (status, _) = send(
+            Request::get(taxii.discovery_url(&TaxiiVersion::V21))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
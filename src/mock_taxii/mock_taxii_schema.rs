@@ -0,0 +1,40 @@
+use super::{HttpsClient, TaxiiDiscovery, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// A TAXII server on 127.0.0.1 over HTTP, serving the same collections
+/// over TAXII 1.1 and 2.1. Stops when dropped.
+pub struct MockTaxii {
+    pub(super) addr: SocketAddr,
+    pub(super) state: Arc<Mutex<TaxiiState>>,
+    pub(super) shutdown: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TaxiiState {
+    /// Base URL, for the absolute addresses TAXII replies carry.
+    pub(super) base: String,
+    pub(super) collections: Vec<MockCollection>,
+    /// Expected Authorization header, if credentials are required.
+    pub(super) authorization: Option<String>,
+    pub(super) next_id: u64,
+    pub(super) requests: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockCollection {
+    pub(super) id: String,
+    pub(super) title: String,
+    /// STIX 2.1 objects.
+    pub(super) objects: Vec<Value>,
+}
+
+/// Reads what a TAXII server (such as MockTaxii) offers, as the
+/// TaxiiCollection records FMC would store. Only handles the replies this
+/// crate's tests need.
+#[derive(Debug, Clone)]
+pub struct TaxiiProbe {
+    pub(super) http: HttpsClient,
+    pub(super) discovery: TaxiiDiscovery,
+}
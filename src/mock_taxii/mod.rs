@@ -0,0 +1,42 @@
+//! A local TAXII 1.1 and 2.1 server for feed-onboarding tests, so they can
+//! run without an external feed, and TaxiiProbe, a small client that reads
+//! a server's collections. Compiled for this crate's tests and behind the
+//! `mock` feature.
+
+use super::error::error_schema::FmcError;
+use super::json::decode;
+use super::taxii::taxii_schema::{TaxiiCollection, TaxiiDiscovery, TaxiiVersion};
+use super::tls::tls_schema::TlsConfig;
+use super::HttpsClient;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+mod mock_taxii_methods;
+pub mod mock_taxii_schema;
+
+/// TAXII 2.1 API root every collection lives under.
+const API_ROOT: &str = "/feeds/";
+/// TAXII 1.1 service addresses, by service type.
+const TAXII1_SERVICES: &[(&str, &str)] = &[
+    ("DISCOVERY", "/taxii-discovery-service"),
+    (
+        "COLLECTION_MANAGEMENT",
+        "/taxii-collection-management-service",
+    ),
+    ("POLL", "/taxii-poll-service"),
+];
+/// Accept and Content-Type of TAXII 2.1 requests and replies.
+const TAXII2_MEDIA_TYPE: &str = "application/taxii+json;version=2.1";
+/// HTTP binding headers every TAXII 1.1 request and reply carries.
+const TAXII1_HEADERS: &[(&str, &str)] = &[
+    ("Content-Type", "application/xml"),
+    (
+        "X-TAXII-Content-Type",
+        "urn:taxii.mitre.org:message:xml:1.1",
+    ),
+    ("X-TAXII-Protocol", "urn:taxii.mitre.org:protocol:http:1.0"),
+    ("X-TAXII-Services", "urn:taxii.mitre.org:services:1.1"),
+];
+/// XML namespace of TAXII 1.1 messages.
+const TAXII1_NAMESPACE: &str = "http://taxii.mitre.org/messages/taxii_xml_binding-1.1";
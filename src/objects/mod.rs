@@ -0,0 +1,12 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Collection, Extra, Reference};
+use super::uri::uri_schema::{Filter, FmcApi, FmcQuery, Object};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+mod objects_methods;
+pub mod objects_schema;
+
+/// FMC accepts at most 1000 objects per bulk POST.
+const MAX_BULK_SIZE: usize = 1000;
@@ -0,0 +1,502 @@
+use super::objects_schema::{FmcObject, ObjectHandle, ObjectList, Objects, WritableObject};
+use super::objects_schema::{Fqdn, Host, Literal, Network, NetworkGroup, Range};
+use super::objects_schema::{IcmpObject, IcmpV4Object, IcmpV6Object, Port, PortObjectGroup};
+use super::objects_schema::{PortRange, Protocol, ProtocolPortObject};
+use super::MAX_BULK_SIZE;
+use super::{Extra, Filter, FmcApi, FmcClient, FmcError, FmcQuery, Object, Paginator, Reference};
+use futures::TryStreamExt;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+impl FmcClient {
+    pub fn objects(&self) -> Objects<'_> {
+        Objects { client: self }
+    }
+}
+
+impl<'c> Objects<'c> {
+    pub fn hosts(&self) -> ObjectHandle<'c, Host> {
+        self.handle()
+    }
+
+    pub fn networks(&self) -> ObjectHandle<'c, Network> {
+        self.handle()
+    }
+
+    pub fn ranges(&self) -> ObjectHandle<'c, Range> {
+        self.handle()
+    }
+
+    pub fn fqdns(&self) -> ObjectHandle<'c, Fqdn> {
+        self.handle()
+    }
+
+    pub fn network_groups(&self) -> ObjectHandle<'c, NetworkGroup> {
+        self.handle()
+    }
+
+    pub fn protocol_ports(&self) -> ObjectHandle<'c, ProtocolPortObject> {
+        self.handle()
+    }
+
+    pub fn port_groups(&self) -> ObjectHandle<'c, PortObjectGroup> {
+        self.handle()
+    }
+
+    pub fn icmpv4(&self) -> ObjectHandle<'c, IcmpV4Object> {
+        self.handle()
+    }
+
+    pub fn icmpv6(&self) -> ObjectHandle<'c, IcmpV6Object> {
+        self.handle()
+    }
+
+    /// All port-type objects. Read-only.
+    pub fn ports(&self) -> ObjectHandle<'c, Port> {
+        self.handle()
+    }
+
+    fn handle<T: FmcObject>(&self) -> ObjectHandle<'c, T> {
+        ObjectHandle {
+            client: self.client,
+            object: PhantomData,
+        }
+    }
+}
+
+impl<'c, T: FmcObject + 'c> ObjectHandle<'c, T> {
+    /// Every object of this type, expanded to full records: the models
+    /// need fields FMC leaves out of summaries.
+    pub fn list(&self) -> Paginator<'c, ObjectList<T>> {
+        self.client
+            .paginate(FmcApi::Objects(T::path(None)))
+            .expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<T, FmcError> {
+        self.client
+            .get_json(FmcApi::Objects(T::path(Some(id.to_string()))))
+            .await
+    }
+
+    /// The object whose name matches exactly, if there is one.
+    pub async fn find(&self, name: &str) -> Result<Option<T>, FmcError> {
+        let mut matches = Box::pin(
+            self.list()
+                .filter(Filter::NameOrValue(name.to_string()))
+                .stream(),
+        );
+        while let Some(object) = matches.try_next().await? {
+            if object.name() == name {
+                return Ok(Some(object));
+            }
+        }
+        Ok(None)
+    }
+
+    /// A reference to the named object, for use in groups and rules.
+    pub async fn reference(&self, name: &str) -> Result<Reference, FmcError> {
+        match self.find(name).await? {
+            Some(object) => object.reference(),
+            None => Err(FmcError::NotFound(name.to_string())),
+        }
+    }
+}
+
+impl<'c, T: WritableObject + 'c> ObjectHandle<'c, T> {
+    /// Creates the object and returns it as FMC stored it (with its ID).
+    pub async fn create(&self, object: &T) -> Result<T, FmcError> {
+        self.client
+            .post_json(FmcApi::Objects(T::path(None)), FmcQuery::new(), object)
+            .await
+    }
+
+    /// Replaces the object with the same ID.
+    pub async fn update(&self, object: &T) -> Result<T, FmcError> {
+        let id = match object.id() {
+            Some(id) => id.to_string(),
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(FmcApi::Objects(T::path(Some(id))), object)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<T, FmcError> {
+        self.client
+            .delete_json(FmcApi::Objects(T::path(Some(id.to_string()))))
+            .await
+    }
+
+    /// Creates many objects with ?bulk=true, 1000 per request.
+    pub async fn bulk_create(&self, objects: &[T]) -> Result<Vec<T>, FmcError> {
+        let mut created = Vec::with_capacity(objects.len());
+        for chunk in objects.chunks(MAX_BULK_SIZE) {
+            let reply: ObjectList<T> = self
+                .client
+                .post_json(
+                    FmcApi::Objects(T::path(None)),
+                    FmcQuery::new().param("bulk", "true"),
+                    &chunk,
+                )
+                .await?;
+            created.extend(reply.items);
+        }
+        Ok(created)
+    }
+}
+
+/// FmcObject and WritableObject for a type stored at /object/{path}. A
+/// trailing `0` reaches through a newtype to the record it wraps.
+macro_rules! writable_object {
+    ($object:ty, $path:ident $(, $inner:tt)?) => {
+        impl FmcObject for $object {
+            fn path(id: Option<String>) -> Object {
+                Object::$path(id)
+            }
+
+            fn id(&self) -> Option<&str> {
+                self$(.$inner)?.id.as_deref()
+            }
+
+            fn name(&self) -> &str {
+                &self$(.$inner)?.name
+            }
+
+            fn type_name(&self) -> &str {
+                &self$(.$inner)?._type
+            }
+        }
+
+        impl WritableObject for $object {}
+    };
+}
+
+writable_object!(Host, Hosts);
+writable_object!(Network, Networks);
+writable_object!(Range, Ranges);
+writable_object!(Fqdn, Fqdns);
+writable_object!(NetworkGroup, NetworkGroups);
+writable_object!(ProtocolPortObject, ProtocolPortObjects);
+writable_object!(PortObjectGroup, PortObjectGroups);
+writable_object!(IcmpV4Object, IcmpV4Objects, 0);
+writable_object!(IcmpV6Object, IcmpV6Objects, 0);
+
+impl FmcObject for Port {
+    fn path(id: Option<String>) -> Object {
+        Object::Ports(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn type_name(&self) -> &str {
+        &self._type
+    }
+}
+
+/// new(name, value) for a value_object! type.
+macro_rules! value_object_new {
+    ($(#[$attr:meta])* $name:ident, $type_name:expr) => {
+        impl $name {
+            $(#[$attr])*
+            pub fn new(name: &str, value: &str) -> $name {
+                $name {
+                    id: None,
+                    _type: $type_name.to_string(),
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    description: None,
+                    overridable: None,
+                    links: None,
+                    metadata: None,
+                    extra: Extra::new(),
+                }
+            }
+        }
+    };
+}
+
+value_object_new!(Host, "Host");
+
+value_object_new!(
+    /// `value` is in CIDR notation, e.g. "10.1.0.0/16".
+    Network,
+    "Network"
+);
+
+value_object_new!(
+    /// `value` is "first-last", e.g. "10.0.0.1-10.0.0.9".
+    Range,
+    "Range"
+);
+
+impl Fqdn {
+    pub fn new(name: &str, value: &str) -> Fqdn {
+        Fqdn {
+            id: None,
+            _type: "FQDN".to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            dns_resolution: None,
+            description: None,
+            overridable: None,
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+}
+
+impl NetworkGroup {
+    pub fn new(name: &str) -> NetworkGroup {
+        NetworkGroup {
+            id: None,
+            _type: "NetworkGroup".to_string(),
+            name: name.to_string(),
+            objects: Vec::new(),
+            literals: Vec::new(),
+            description: None,
+            overridable: None,
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// Adds a member object (or nested group) by reference.
+    pub fn with_object(mut self, object: Reference) -> NetworkGroup {
+        self.objects.push(object);
+        self
+    }
+
+    pub fn with_literal(mut self, literal: Literal) -> NetworkGroup {
+        self.literals.push(literal);
+        self
+    }
+}
+
+impl Literal {
+    pub fn host(value: &str) -> Literal {
+        Literal {
+            _type: "Host".to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn network(value: &str) -> Literal {
+        Literal {
+            _type: "Network".to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn range(value: &str) -> Literal {
+        Literal {
+            _type: "Range".to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl ProtocolPortObject {
+    pub fn new(name: &str, protocol: Protocol, port: PortRange) -> ProtocolPortObject {
+        ProtocolPortObject {
+            id: None,
+            _type: "ProtocolPortObject".to_string(),
+            name: name.to_string(),
+            protocol,
+            port,
+            description: None,
+            overridable: None,
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+}
+
+impl PortObjectGroup {
+    pub fn new(name: &str) -> PortObjectGroup {
+        PortObjectGroup {
+            id: None,
+            _type: "PortObjectGroup".to_string(),
+            name: name.to_string(),
+            objects: Vec::new(),
+            description: None,
+            overridable: None,
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+
+    pub fn with_object(mut self, object: Reference) -> PortObjectGroup {
+        self.objects.push(object);
+        self
+    }
+}
+
+impl IcmpObject {
+    fn new(_type: &str, name: &str, icmp_type: Option<&str>, code: Option<u8>) -> IcmpObject {
+        IcmpObject {
+            id: None,
+            _type: _type.to_string(),
+            name: name.to_string(),
+            icmp_type: icmp_type.map(String::from),
+            code,
+            description: None,
+            overridable: None,
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+}
+
+impl IcmpV4Object {
+    pub fn new(name: &str, icmp_type: Option<&str>, code: Option<u8>) -> IcmpV4Object {
+        IcmpV4Object(IcmpObject::new("ICMPV4Object", name, icmp_type, code))
+    }
+}
+
+impl IcmpV6Object {
+    pub fn new(name: &str, icmp_type: Option<&str>, code: Option<u8>) -> IcmpV6Object {
+        IcmpV6Object(IcmpObject::new("ICMPV6Object", name, icmp_type, code))
+    }
+}
+
+impl From<String> for Protocol {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "TCP" => Protocol::Tcp,
+            "UDP" => Protocol::Udp,
+            _ => Protocol::Other(value),
+        }
+    }
+}
+
+impl From<Protocol> for String {
+    fn from(value: Protocol) -> Self {
+        match value {
+            Protocol::Tcp => "TCP".to_string(),
+            Protocol::Udp => "UDP".to_string(),
+            Protocol::Other(value) => value,
+        }
+    }
+}
+
+impl PortRange {
+    /// A validated low-high range. Equal ends collapse to a single port.
+    pub fn range(low: u16, high: u16) -> Result<PortRange, FmcError> {
+        if low == 0 || low > high {
+            return Err(FmcError::InvalidPort(
+                [low.to_string(), high.to_string()].join("-"),
+            ));
+        }
+        match low == high {
+            true => Ok(PortRange::Single(low)),
+            false => Ok(PortRange::Range(low, high)),
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        *self == PortRange::Any
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = FmcError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("any") {
+            return Ok(PortRange::Any);
+        }
+
+        let port = |part: &str| match part.trim().parse::<u16>() {
+            Ok(port) if port > 0 => Ok(port),
+            _ => Err(FmcError::InvalidPort(value.to_string())),
+        };
+        match value.find('-') {
+            Some(i) => PortRange::range(port(&value[..i])?, port(&value[i + 1..])?),
+            None => Ok(PortRange::Single(port(value)?)),
+        }
+    }
+}
+
+impl TryFrom<String> for PortRange {
+    type Error = FmcError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PortRange> for String {
+    fn from(value: PortRange) -> Self {
+        match value {
+            PortRange::Any => "any".to_string(),
+            PortRange::Single(port) => port.to_string(),
+            PortRange::Range(low, high) => [low.to_string(), high.to_string()].join("-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn fn_list_full_records() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let objects = client.objects();
+
+        let hosts = objects.hosts().list().collect_all().await?;
+        assert_eq!(hosts.len(), 3);
+        assert!(hosts.iter().all(|host| !host.value.is_empty()));
+        let networks = objects.networks().list().collect_all().await?;
+        assert!(!networks.is_empty());
+        assert!(mock
+            .requests()
+            .iter()
+            .all(|r| !r.contains("/object/") || r.contains("expanded=true")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_list_port_objects() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        mock.fixture(
+            "object/protocolportobjects",
+            vec![
+                json!({"id": "pp-1", "type": "ProtocolPortObject", "name": "HTTPS",
+                       "protocol": "TCP", "port": "443"}),
+                json!({"id": "pp-2", "type": "ProtocolPortObject", "name": "GRE",
+                       "protocol": "47"}),
+            ],
+        );
+        let client = mock.client().await;
+
+        let ports = client
+            .objects()
+            .protocol_ports()
+            .list()
+            .collect_all()
+            .await?;
+        assert_eq!(ports[0].protocol, Protocol::Tcp);
+        assert_eq!(ports[0].port, PortRange::Single(443));
+        assert_eq!(ports[1].protocol, Protocol::Other("47".to_string()));
+        assert_eq!(ports[1].port, PortRange::Any);
+        Ok(())
+    }
+}
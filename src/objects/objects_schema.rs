@@ -0,0 +1,394 @@
+use super::{core, Collection, DeserializeOwned, Extra, FmcClient, FmcError, Object, Reference};
+use super::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// A type that lives under /object/{type}.
+pub trait FmcObject: Serialize + DeserializeOwned {
+    /// The collection (None) or single object (Some) path for this type.
+    fn path(id: Option<String>) -> Object;
+    fn id(&self) -> Option<&str>;
+    fn name(&self) -> &str;
+    fn type_name(&self) -> &str;
+
+    /// A {id, type, name} pointer to this object. Fails if it has no ID yet.
+    fn reference(&self) -> Result<Reference, FmcError> {
+        match self.id() {
+            Some(id) => Ok(Reference {
+                id: id.to_string(),
+                _type: Some(self.type_name().to_string()),
+                name: Some(self.name().to_string()),
+            }),
+            None => Err(FmcError::MissingField("id")),
+        }
+    }
+}
+
+/// Object types that can be created, updated and deleted.
+pub trait WritableObject: FmcObject {}
+
+/// One page of an /object collection (or any other plain FMC collection).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(bound(deserialize = "T: DeserializeOwned"))]
+pub struct ObjectList<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<core::Links>,
+    #[serde(default)]
+    pub items: Vec<T>,
+    #[serde(default)]
+    pub paging: core::Paging,
+}
+
+impl<T: DeserializeOwned> Collection for ObjectList<T> {
+    type Item = T;
+
+    fn paging(&self) -> &core::Paging {
+        &self.paging
+    }
+
+    fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// Declares a Host, Network or Range: a name for one address `value`.
+macro_rules! value_object {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+        pub struct $name {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub id: Option<String>,
+            #[serde(rename = "type")]
+            pub _type: String,
+            pub name: String,
+            pub value: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub description: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub overridable: Option<bool>,
+            #[serde(default, skip_serializing)]
+            pub links: Option<core::Links>,
+            #[serde(default, skip_serializing)]
+            pub metadata: Option<core::MetaData>,
+            #[serde(flatten)]
+            pub extra: Extra,
+        }
+    };
+}
+
+value_object!(
+    /// /object/hosts
+    Host
+);
+
+value_object!(
+    /// /object/networks
+    Network
+);
+
+value_object!(
+    /// /object/ranges, value is "10.0.0.1-10.0.0.9"
+    Range
+);
+
+// /object/fqdns
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Fqdn {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    pub value: String,
+    /// IPV4_ONLY, IPV6_ONLY or IPV4_AND_IPV6
+    #[serde(rename = "dnsResolution", skip_serializing_if = "Option::is_none")]
+    pub dns_resolution: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /object/networkgroups
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    /// Member objects (hosts, networks, ranges, fqdns and nested groups).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<Reference>,
+    /// Inline addresses that aren't objects of their own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<Literal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// An inline group member: {"type": "Host", "value": "10.1.1.1"}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Literal {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub value: String,
+}
+
+// /object/protocolportobjects
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProtocolPortObject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    pub protocol: Protocol,
+    /// Left out of the JSON entirely when Any.
+    #[serde(default, skip_serializing_if = "PortRange::is_any")]
+    pub port: PortRange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /object/portobjectgroups
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PortObjectGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    /// Member protocol-port, ICMP and nested group objects.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<Reference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /object/icmpv4objects and /object/icmpv6objects. `_type` tells them apart.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IcmpObject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    /// ICMP type number as FMC sends it, e.g. "8". None matches any type.
+    #[serde(rename = "icmpType", skip_serializing_if = "Option::is_none")]
+    pub icmp_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridable: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// An ICMPv4 object; see IcmpObject.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct IcmpV4Object(pub IcmpObject);
+
+/// An ICMPv6 object; see IcmpObject.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct IcmpV6Object(pub IcmpObject);
+
+// /object/ports (read-only). Mixes protocol-port and ICMP objects.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Port {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<Protocol>,
+    #[serde(default, skip_serializing_if = "PortRange::is_any")]
+    pub port: PortRange,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// protocol: "TCP", "UDP", or whatever else FMC sends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Other(String),
+}
+
+/// port: a single port, "low-high", or Any (no port field).
+/// Parse with "443".parse() or build with PortRange::range().
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum PortRange {
+    #[default]
+    Any,
+    Single(u16),
+    Range(u16, u16),
+}
+
+/// Entry point for /object, from FmcClient::objects().
+#[derive(Debug)]
+pub struct Objects<'c> {
+    pub(super) client: &'c FmcClient,
+}
+
+/// CRUD for one object type, e.g. client.objects().hosts().
+#[derive(Debug)]
+pub struct ObjectHandle<'c, T> {
+    pub(super) client: &'c FmcClient,
+    pub(super) object: PhantomData<T>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // GET /api/fmc_config/v1/domain/{domainUUID}/object/networkgroups/{objectId}
+    #[test]
+    fn network_group_test() {
+        let raw_str = r#"{
+            "id": "0050568A-7F57-0ed3-0000-004294980802",
+            "type": "NetworkGroup",
+            "name": "Branch-Nets",
+            "objects": [
+                {"type": "Network", "id": "0050568A-7F57-0ed3-0000-004294980784", "name": "Branch-A"},
+                {"type": "NetworkGroup", "id": "0050568A-7F57-0ed3-0000-004294980790", "name": "Branch-B-Nets"}
+            ],
+            "literals": [
+                {"type": "Host", "value": "10.20.0.1"},
+                {"type": "Network", "value": "10.30.0.0/16"}
+            ],
+            "overridable": false,
+            "links": {
+                "self": "https://fmc/api/fmc_config/v1/domain/default/object/networkgroups/0050568A-7F57-0ed3-0000-004294980802"
+            },
+            "metadata": {"readOnly": {"state": false}}
+        }"#;
+
+        let parsed: NetworkGroup = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.objects.len(), 2);
+        assert_eq!(parsed.objects[1]._type.as_deref(), Some("NetworkGroup"));
+        assert_eq!(parsed.literals[1].value, "10.30.0.0/16");
+
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert!(written.get("links").is_none());
+        assert!(written.get("metadata").is_none());
+        assert_eq!(written["literals"][0]["type"], "Host");
+    }
+
+    // GET /api/fmc_config/v1/domain/{domainUUID}/object/protocolportobjects?expanded=true
+    #[test]
+    fn protocol_port_test() {
+        let raw_str = r#"{
+            "items": [
+                {"id": "1834bd00-38bb-11e2-86aa-62f0c593a59a", "type": "ProtocolPortObject",
+                 "name": "HTTPS", "protocol": "TCP", "port": "443"},
+                {"id": "1834d812-38bb-11e2-86aa-62f0c593a59a", "type": "ProtocolPortObject",
+                 "name": "High-UDP", "protocol": "UDP", "port": "1024-65535"},
+                {"id": "1834e5be-38bb-11e2-86aa-62f0c593a59a", "type": "ProtocolPortObject",
+                 "name": "GRE", "protocol": "47"}
+            ],
+            "paging": {"offset": 0, "limit": 25, "count": 3, "pages": 1}
+        }"#;
+
+        let parsed: ObjectList<ProtocolPortObject> = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.items[0].port, PortRange::Single(443));
+        assert_eq!(parsed.items[1].protocol, Protocol::Udp);
+        assert_eq!(parsed.items[1].port, PortRange::Range(1024, 65535));
+        assert_eq!(parsed.items[2].protocol, Protocol::Other("47".to_string()));
+        assert_eq!(parsed.items[2].port, PortRange::Any);
+
+        let written = serde_json::to_value(&parsed.items[2]).unwrap();
+        assert!(written.get("port").is_none());
+        let written = serde_json::to_value(&parsed.items[1]).unwrap();
+        assert_eq!(written["port"], "1024-65535");
+
+        let bad =
+            r#"{"type": "ProtocolPortObject", "name": "x", "protocol": "TCP", "port": "90-80"}"#;
+        assert!(serde_json::from_str::<ProtocolPortObject>(bad).is_err());
+    }
+
+    #[test]
+    fn port_range_test() {
+        assert_eq!("any".parse::<PortRange>().unwrap(), PortRange::Any);
+        assert_eq!(
+            " 8080 ".parse::<PortRange>().unwrap(),
+            PortRange::Single(8080)
+        );
+        assert_eq!(PortRange::range(80, 80).unwrap(), PortRange::Single(80));
+        assert!(PortRange::range(0, 10).is_err());
+        assert!("70000".parse::<PortRange>().is_err());
+        assert!("http".parse::<PortRange>().is_err());
+        assert_eq!(String::from(PortRange::Range(1, 1023)), "1-1023");
+    }
+
+    #[test]
+    fn new_objects_test() {
+        let host = serde_json::to_value(Host::new("web-01", "10.0.0.10")).unwrap();
+        assert_eq!(
+            host,
+            serde_json::json!({"type": "Host", "name": "web-01", "value": "10.0.0.10"})
+        );
+
+        let group = NetworkGroup::new("dmz")
+            .with_object(Reference {
+                id: "abc".to_string(),
+                _type: Some("Host".to_string()),
+                name: None,
+            })
+            .with_literal(Literal::network("192.0.2.0/24"));
+        let group = serde_json::to_value(group).unwrap();
+        assert_eq!(group["objects"][0]["id"], "abc");
+        assert_eq!(group["literals"][0]["type"], "Network");
+
+        let icmp = serde_json::to_value(IcmpV4Object::new("echo", Some("8"), None)).unwrap();
+        assert_eq!(
+            icmp,
+            serde_json::json!({"type": "ICMPV4Object", "name": "echo", "icmpType": "8"})
+        );
+    }
+}
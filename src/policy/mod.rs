@@ -0,0 +1,12 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra, Reference};
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::{Filter, FmcApi, FmcQuery};
+use serde::{Deserialize, Serialize};
+
+mod policy_methods;
+pub mod policy_schema;
+
+/// FMC accepts at most 1000 rules per bulk POST.
+const MAX_BULK_SIZE: usize = 1000;
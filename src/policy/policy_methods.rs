@@ -0,0 +1,429 @@
+use super::policy_schema::{AccessPolicies, AccessPolicy, AccessRule, AccessRules, Category};
+use super::policy_schema::{Condition, DefaultAction, RuleAction, RulePosition, Section};
+use super::MAX_BULK_SIZE;
+use super::{Extra, Filter, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator};
+
+impl FmcClient {
+    pub fn access_policies(&self) -> AccessPolicies<'_> {
+        AccessPolicies { client: self }
+    }
+}
+
+impl<'c> AccessPolicies<'c> {
+    pub fn list(&self) -> Paginator<'c, ObjectList<AccessPolicy>> {
+        self.client.paginate(FmcApi::AccessPolicies(None))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<AccessPolicy, FmcError> {
+        self.client
+            .get_json(FmcApi::access_policy(id).record())
+            .await
+    }
+
+    /// The policy with exactly this name, if there is one.
+    pub async fn find(&self, name: &str) -> Result<Option<AccessPolicy>, FmcError> {
+        let policies = self
+            .list()
+            .filter(Filter::Name(name.to_string()))
+            .expanded(true)
+            .collect_all()
+            .await?;
+        Ok(policies.into_iter().find(|policy| policy.name == name))
+    }
+
+    pub async fn create(&self, policy: &AccessPolicy) -> Result<AccessPolicy, FmcError> {
+        self.client
+            .post_json(FmcApi::AccessPolicies(None), FmcQuery::new(), policy)
+            .await
+    }
+
+    pub async fn update(&self, policy: &AccessPolicy) -> Result<AccessPolicy, FmcError> {
+        let id = match &policy.id {
+            Some(id) => id,
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(FmcApi::access_policy(id).record(), policy)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<AccessPolicy, FmcError> {
+        self.client
+            .delete_json(FmcApi::access_policy(id).record())
+            .await
+    }
+
+    /// Rules, categories and default action of the policy with this ID.
+    pub fn rules(&self, policy_id: &str) -> AccessRules<'c> {
+        AccessRules {
+            client: self.client,
+            policy: policy_id.to_string(),
+        }
+    }
+}
+
+impl<'c> AccessRules<'c> {
+    /// Every rule in rulebase order, expanded: summaries leave out the
+    /// action every rule needs.
+    pub fn list(&self) -> Paginator<'c, ObjectList<AccessRule>> {
+        self.client
+            .paginate(FmcApi::access_policy(&self.policy).access_rules())
+            .expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<AccessRule, FmcError> {
+        self.client
+            .get_json(FmcApi::access_policy(&self.policy).access_rule(id))
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        rule: &AccessRule,
+        position: RulePosition,
+    ) -> Result<AccessRule, FmcError> {
+        self.client
+            .post_json(
+                FmcApi::access_policy(&self.policy).access_rules(),
+                position.query(),
+                rule,
+            )
+            .await
+    }
+
+    /// Creates many rules with ?bulk=true, keeping their order.
+    pub async fn bulk_create(
+        &self,
+        rules: &[AccessRule],
+        mut position: RulePosition,
+    ) -> Result<Vec<AccessRule>, FmcError> {
+        let mut created = Vec::with_capacity(rules.len());
+        let mut chunks = rules.chunks(MAX_BULK_SIZE).peekable();
+        while let Some(chunk) = chunks.next() {
+            let reply: ObjectList<AccessRule> = self
+                .client
+                .post_json(
+                    FmcApi::access_policy(&self.policy).access_rules(),
+                    position.query().param("bulk", "true"),
+                    &chunk,
+                )
+                .await?;
+
+            // Keep later chunks behind the ones already inserted.
+            let anchored = matches!(
+                position,
+                RulePosition::InsertBefore(_) | RulePosition::InsertAfter(_)
+            );
+            if anchored && chunks.peek().is_some() {
+                let index = self.last_index(&reply.items).await?;
+                position = RulePosition::InsertAfter(index);
+            }
+            created.extend(reply.items);
+        }
+        Ok(created)
+    }
+
+    /// Where the last of `rules` landed: its ruleIndex, or the index FMC
+    /// gives when the rule is fetched again if the bulk reply had none.
+    async fn last_index(&self, rules: &[AccessRule]) -> Result<u32, FmcError> {
+        let last = match rules.last() {
+            Some(last) => last,
+            None => return Err(FmcError::MissingField("ruleIndex")),
+        };
+        if let Some(index) = last.rule_index() {
+            return Ok(index);
+        }
+        let id = match &last.id {
+            Some(id) => id,
+            None => return Err(FmcError::MissingField("id")),
+        };
+        match self.get(id).await?.rule_index() {
+            Some(index) => Ok(index),
+            None => Err(FmcError::MissingField("ruleIndex")),
+        }
+    }
+
+    pub async fn update(&self, rule: &AccessRule) -> Result<AccessRule, FmcError> {
+        let id = match &rule.id {
+            Some(id) => id,
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(FmcApi::access_policy(&self.policy).access_rule(id), rule)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<AccessRule, FmcError> {
+        self.client
+            .delete_json(FmcApi::access_policy(&self.policy).access_rule(id))
+            .await
+    }
+
+    pub fn categories(&self) -> Paginator<'c, ObjectList<Category>> {
+        self.client
+            .paginate(FmcApi::access_policy(&self.policy).categories())
+    }
+
+    /// Adds a category at the end of the given section.
+    pub async fn create_category(
+        &self,
+        name: &str,
+        section: Section,
+    ) -> Result<Category, FmcError> {
+        let category = Category {
+            id: None,
+            _type: "Category".to_string(),
+            name: name.to_string(),
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        };
+        self.client
+            .post_json(
+                FmcApi::access_policy(&self.policy).categories(),
+                RulePosition::Section(section).query(),
+                &category,
+            )
+            .await
+    }
+
+    pub async fn delete_category(&self, id: &str) -> Result<Category, FmcError> {
+        self.client
+            .delete_json(FmcApi::access_policy(&self.policy).category(id))
+            .await
+    }
+
+    /// The policy's default action. FMC keeps exactly one per policy.
+    pub async fn default_action(&self) -> Result<DefaultAction, FmcError> {
+        let actions: ObjectList<DefaultAction> = self
+            .client
+            .paginate(FmcApi::access_policy(&self.policy).default_actions())
+            .expanded(true)
+            .page(0)
+            .await?;
+        match actions.items.into_iter().next() {
+            Some(action) => Ok(action),
+            None => Err(FmcError::NotFound("default action".to_string())),
+        }
+    }
+
+    pub async fn update_default_action(
+        &self,
+        action: &DefaultAction,
+    ) -> Result<DefaultAction, FmcError> {
+        let id = match &action.id {
+            Some(id) => id,
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(
+                FmcApi::access_policy(&self.policy).default_action(id),
+                action,
+            )
+            .await
+    }
+}
+
+impl AccessPolicy {
+    pub fn new(name: &str, default_action: RuleAction) -> AccessPolicy {
+        AccessPolicy {
+            id: None,
+            _type: "AccessPolicy".to_string(),
+            name: name.to_string(),
+            description: None,
+            default_action: Some(DefaultAction {
+                id: None,
+                _type: Some("AccessPolicyDefaultAction".to_string()),
+                action: default_action,
+                log_begin: None,
+                log_end: None,
+                send_events_to_fmc: None,
+                intrusion_policy: None,
+                links: None,
+                extra: Extra::new(),
+            }),
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+}
+
+impl AccessRule {
+    /// An enabled rule matching everything; fill in the conditions.
+    pub fn new(name: &str, action: RuleAction) -> AccessRule {
+        AccessRule {
+            id: None,
+            _type: "AccessRule".to_string(),
+            name: name.to_string(),
+            action,
+            enabled: true,
+            source_zones: None,
+            destination_zones: None,
+            source_networks: None,
+            destination_networks: None,
+            source_ports: None,
+            destination_ports: None,
+            applications: None,
+            urls: None,
+            users: None,
+            ips_policy: None,
+            file_policy: None,
+            variable_set: None,
+            log_begin: None,
+            log_end: None,
+            log_files: None,
+            send_events_to_fmc: None,
+            new_comments: Vec::new(),
+            links: None,
+            metadata: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// 1-based position in the policy, as FMC reported it.
+    pub fn rule_index(&self) -> Option<u32> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.rule_index)
+    }
+}
+
+impl Condition {
+    pub fn objects(objects: Vec<super::Reference>) -> Condition {
+        Condition {
+            objects,
+            ..Condition::default()
+        }
+    }
+}
+
+impl RulePosition {
+    /// The insertBefore/insertAfter/section/category query parameter.
+    pub fn query(&self) -> FmcQuery {
+        match self {
+            RulePosition::End => FmcQuery::new(),
+            RulePosition::InsertBefore(index) => {
+                FmcQuery::new().param("insertBefore", &index.to_string())
+            }
+            RulePosition::InsertAfter(index) => {
+                FmcQuery::new().param("insertAfter", &index.to_string())
+            }
+            RulePosition::Section(Section::Mandatory) => {
+                FmcQuery::new().param("section", "mandatory")
+            }
+            RulePosition::Section(Section::Default) => FmcQuery::new().param("section", "default"),
+            RulePosition::Category(name) => FmcQuery::new().param("category", name),
+        }
+    }
+}
+
+impl From<String> for RuleAction {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "ALLOW" => RuleAction::Allow,
+            "TRUST" => RuleAction::Trust,
+            "BLOCK" => RuleAction::Block,
+            "BLOCK_RESET" => RuleAction::BlockReset,
+            "BLOCK_INTERACTIVE" => RuleAction::BlockInteractive,
+            "BLOCK_RESET_INTERACTIVE" => RuleAction::BlockResetInteractive,
+            "MONITOR" => RuleAction::Monitor,
+            _ => RuleAction::Other(value),
+        }
+    }
+}
+
+impl From<RuleAction> for String {
+    fn from(value: RuleAction) -> Self {
+        match value {
+            RuleAction::Allow => "ALLOW".to_string(),
+            RuleAction::Trust => "TRUST".to_string(),
+            RuleAction::Block => "BLOCK".to_string(),
+            RuleAction::BlockReset => "BLOCK_RESET".to_string(),
+            RuleAction::BlockInteractive => "BLOCK_INTERACTIVE".to_string(),
+            RuleAction::BlockResetInteractive => "BLOCK_RESET_INTERACTIVE".to_string(),
+            RuleAction::Monitor => "MONITOR".to_string(),
+            RuleAction::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fn_rule_position_query() {
+        let api = FmcApi::access_policy("acp-1").access_rules();
+        let render = |position: RulePosition| position.query().render(&api).unwrap();
+
+        assert_eq!(render(RulePosition::End), "");
+        assert_eq!(render(RulePosition::InsertBefore(4)), "insertBefore=4");
+        assert_eq!(render(RulePosition::InsertAfter(9)), "insertAfter=9");
+        assert_eq!(
+            render(RulePosition::Section(Section::Mandatory)),
+            "section=mandatory"
+        );
+        assert_eq!(
+            render(RulePosition::Category("Web Rules".to_string())),
+            "category=Web%20Rules"
+        );
+    }
+
+    #[test]
+    fn fn_new_rule() {
+        let mut rule = AccessRule::new("Allow-DNS", RuleAction::Allow);
+        rule.destination_networks = Some(Condition::objects(vec![super::super::Reference {
+            id: "net-1".to_string(),
+            _type: Some("Network".to_string()),
+            name: None,
+        }]));
+
+        let written = serde_json::to_value(&rule).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "type": "AccessRule",
+                "name": "Allow-DNS",
+                "action": "ALLOW",
+                "enabled": true,
+                "destinationNetworks": {"objects": [{"id": "net-1", "type": "Network"}]}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn fn_bulk_create_order() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let rule =
+            |id: &str| json!({"id": id, "type": "AccessRule", "name": id, "action": "BLOCK"});
+        mock.fixture(
+            "policy/accesspolicies/acp-1/accessrules",
+            vec![rule("first"), rule("last")],
+        );
+        let client = mock.client().await;
+        let rules = client.access_policies().rules("acp-1");
+
+        // Bulk replies carry no ruleIndex, so chunk two is placed after
+        // the index FMC reports for the last rule of chunk one.
+        let new: Vec<AccessRule> = (0..MAX_BULK_SIZE + 2)
+            .map(|i| AccessRule::new(&format!("r{}", i), RuleAction::Allow))
+            .collect();
+        let created = rules
+            .bulk_create(&new, RulePosition::InsertAfter(1))
+            .await?;
+        assert_eq!(created.len(), new.len());
+        assert!(created.iter().all(|rule| rule.rule_index().is_none()));
+
+        let listed = rules.list().collect_all().await?;
+        let names: Vec<&str> = listed.iter().map(|rule| rule.name.as_str()).collect();
+        let mut expected = vec!["first".to_string()];
+        expected.extend((0..MAX_BULK_SIZE + 2).map(|i| format!("r{}", i)));
+        expected.push("last".to_string());
+        assert_eq!(names, expected);
+        assert_eq!(listed[0].action, RuleAction::Block);
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use super::{core, Deserialize, Extra, FmcClient, Reference, Serialize};
+
+// /policy/accesspolicies
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccessPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "defaultAction", skip_serializing_if = "Option::is_none")]
+    pub default_action: Option<DefaultAction>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<core::MetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /policy/accesspolicies/{id}/defaultactions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DefaultAction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    pub action: RuleAction,
+    #[serde(rename = "logBegin", skip_serializing_if = "Option::is_none")]
+    pub log_begin: Option<bool>,
+    #[serde(rename = "logEnd", skip_serializing_if = "Option::is_none")]
+    pub log_end: Option<bool>,
+    #[serde(rename = "sendEventsToFMC", skip_serializing_if = "Option::is_none")]
+    pub send_events_to_fmc: Option<bool>,
+    #[serde(rename = "intrusionPolicy", skip_serializing_if = "Option::is_none")]
+    pub intrusion_policy: Option<Reference>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /policy/accesspolicies/{id}/accessrules
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccessRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    pub action: RuleAction,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(rename = "sourceZones", skip_serializing_if = "Option::is_none")]
+    pub source_zones: Option<Condition>,
+    #[serde(rename = "destinationZones", skip_serializing_if = "Option::is_none")]
+    pub destination_zones: Option<Condition>,
+    #[serde(rename = "sourceNetworks", skip_serializing_if = "Option::is_none")]
+    pub source_networks: Option<Condition>,
+    #[serde(
+        rename = "destinationNetworks",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub destination_networks: Option<Condition>,
+    #[serde(rename = "sourcePorts", skip_serializing_if = "Option::is_none")]
+    pub source_ports: Option<Condition>,
+    #[serde(rename = "destinationPorts", skip_serializing_if = "Option::is_none")]
+    pub destination_ports: Option<Condition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applications: Option<ApplicationCondition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urls: Option<UrlCondition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<Condition>,
+    #[serde(rename = "ipsPolicy", skip_serializing_if = "Option::is_none")]
+    pub ips_policy: Option<Reference>,
+    #[serde(rename = "filePolicy", skip_serializing_if = "Option::is_none")]
+    pub file_policy: Option<Reference>,
+    #[serde(rename = "variableSet", skip_serializing_if = "Option::is_none")]
+    pub variable_set: Option<Reference>,
+    #[serde(rename = "logBegin", skip_serializing_if = "Option::is_none")]
+    pub log_begin: Option<bool>,
+    #[serde(rename = "logEnd", skip_serializing_if = "Option::is_none")]
+    pub log_end: Option<bool>,
+    #[serde(rename = "logFiles", skip_serializing_if = "Option::is_none")]
+    pub log_files: Option<bool>,
+    #[serde(rename = "sendEventsToFMC", skip_serializing_if = "Option::is_none")]
+    pub send_events_to_fmc: Option<bool>,
+    #[serde(rename = "newComments", default, skip_serializing_if = "Vec::is_empty")]
+    pub new_comments: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<AccessRuleMetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Where a rule sits in its policy; read-only.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccessRuleMetaData {
+    #[serde(rename = "ruleIndex", skip_serializing_if = "Option::is_none")]
+    pub rule_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(rename = "accessPolicy", skip_serializing_if = "Option::is_none")]
+    pub access_policy: Option<Reference>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// A zone, network, port or user match: objects by reference plus
+/// inline literals.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Condition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<Reference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<RuleLiteral>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// An inline network ({"type": "Network", "value": ...}) or port
+/// ({"type": "PortLiteral", "protocol": "6", "port": "443"}) literal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RuleLiteral {
+    #[serde(rename = "type")]
+    pub _type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ApplicationCondition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub applications: Vec<Reference>,
+    #[serde(
+        rename = "applicationFilters",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub application_filters: Vec<Reference>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct UrlCondition {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objects: Vec<Reference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<UrlLiteral>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// {"type": "Url", "url": "example.com"}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UrlLiteral {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub url: String,
+}
+
+/// action: "ALLOW", "TRUST", "BLOCK", ... Default actions also use this,
+/// with values like "NETWORK_DISCOVERY" landing in Other.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum RuleAction {
+    Allow,
+    Trust,
+    Block,
+    BlockReset,
+    BlockInteractive,
+    BlockResetInteractive,
+    Monitor,
+    Other(String),
+}
+
+// /policy/accesspolicies/{id}/categories
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Category {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub name: String,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(default, skip_serializing)]
+    pub metadata: Option<AccessRuleMetaData>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Where create() puts new rules. FMC appends to the default section
+/// when nothing is given.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RulePosition {
+    End,
+    /// Before the rule with this 1-based index.
+    InsertBefore(u32),
+    /// After the rule with this 1-based index.
+    InsertAfter(u32),
+    Section(Section),
+    /// At the end of the named category.
+    Category(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Mandatory,
+    Default,
+}
+
+/// Entry point for /policy/accesspolicies, from FmcClient::access_policies().
+#[derive(Debug)]
+pub struct AccessPolicies<'c> {
+    pub(super) client: &'c FmcClient,
+}
+
+/// Rules, categories and the default action of one access policy.
+#[derive(Debug)]
+pub struct AccessRules<'c> {
+    pub(super) client: &'c FmcClient,
+    pub(super) policy: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // GET /api/fmc_config/v1/domain/{domainUUID}/policy/accesspolicies/{containerUUID}/accessrules/{objectId}
+    #[test]
+    fn access_rule_test() {
+        let raw_str = r#"{
+            "id": "005056BB-0B24-0ed3-0000-000268434433",
+            "type": "AccessRule",
+            "name": "Allow-Web",
+            "action": "ALLOW",
+            "enabled": true,
+            "sourceZones": {"objects": [{"id": "zone-in", "type": "SecurityZone", "name": "inside"}]},
+            "destinationNetworks": {
+                "objects": [{"id": "net-1", "type": "Network", "name": "DMZ"}],
+                "literals": [{"type": "Host", "value": "192.0.2.10"}]
+            },
+            "destinationPorts": {
+                "literals": [{"type": "PortLiteral", "protocol": "6", "port": "443"}]
+            },
+            "urls": {"literals": [{"type": "Url", "url": "example.com"}]},
+            "logEnd": true,
+            "sendEventsToFMC": true,
+            "commentHistoryList": [{"comment": "added by pipeline"}],
+            "metadata": {
+                "ruleIndex": 3,
+                "section": "Mandatory",
+                "category": "--Undefined--",
+                "accessPolicy": {"id": "acp-1", "type": "AccessPolicy", "name": "Branch"}
+            }
+        }"#;
+
+        let parsed: AccessRule = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(parsed.action, RuleAction::Allow);
+        assert_eq!(parsed.metadata.as_ref().unwrap().rule_index, Some(3));
+        let ports = parsed.destination_ports.as_ref().unwrap();
+        assert_eq!(ports.literals[0].port.as_deref(), Some("443"));
+        assert_eq!(parsed.urls.as_ref().unwrap().literals[0].url, "example.com");
+
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert!(written.get("metadata").is_none());
+        assert_eq!(written["action"], "ALLOW");
+        assert_eq!(
+            written["commentHistoryList"][0]["comment"],
+            "added by pipeline"
+        );
+    }
+
+    #[test]
+    fn default_action_test() {
+        let raw_str = r#"{"id": "da-1", "type": "AccessPolicyDefaultAction",
+                          "action": "NETWORK_DISCOVERY", "logEnd": false}"#;
+        let parsed: DefaultAction = serde_json::from_str(raw_str).unwrap();
+        assert_eq!(
+            parsed.action,
+            RuleAction::Other("NETWORK_DISCOVERY".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap()["action"],
+            "NETWORK_DISCOVERY"
+        );
+    }
+}
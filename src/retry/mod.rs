@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, Method, StatusCode};
+use std::fmt;
+use std::time::Duration;
+
+mod retry_methods;
+pub mod retry_schema;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Rate limiting, and the gateway errors FMC returns while it is busy
+/// (e.g. 503 during a deployment).
+const DEFAULT_STATUSES: [u16; 4] = [429, 502, 503, 504];
@@ -0,0 +1,211 @@
+use super::retry_schema::{ExponentialBackoff, Failure, NoRetry, RetryPolicy};
+use super::{DateTime, Duration, HeaderMap, Method, StatusCode, Utc};
+use super::{DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY, DEFAULT_MAX_RETRIES, DEFAULT_STATUSES};
+use rand::Rng;
+
+impl ExponentialBackoff {
+    /// 3 retries of 429/502/503/504 and connection failures, starting
+    /// at 500ms and doubling up to 30s.
+    pub fn new() -> ExponentialBackoff {
+        ExponentialBackoff {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+            statuses: DEFAULT_STATUSES
+                .iter()
+                .map(|code| StatusCode::from_u16(*code).unwrap())
+                .collect(),
+            retry_non_idempotent: false,
+        }
+    }
+
+    pub fn max_retries(mut self, retries: u32) -> ExponentialBackoff {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Wait before the first retry; doubles on each one after.
+    pub fn base_delay(mut self, delay: Duration) -> ExponentialBackoff {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Longest wait before a retry, Retry-After included.
+    pub fn max_delay(mut self, delay: Duration) -> ExponentialBackoff {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Waits a random 50-100% of each delay so clients that failed
+    /// together don't retry together. On by default.
+    pub fn jitter(mut self, jitter: bool) -> ExponentialBackoff {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the list of retryable statuses.
+    pub fn retry_on(mut self, statuses: &[StatusCode]) -> ExponentialBackoff {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Also resends POSTs after 5xx and broken connections. Only safe
+    /// when a duplicate create is harmless.
+    pub fn retry_non_idempotent(mut self, retry: bool) -> ExponentialBackoff {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    fn delay(&self, retries: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(1 << retries.min(16))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        match self.jitter {
+            true => delay.mul_f64(rand::thread_rng().gen_range(0.5, 1.0)),
+            false => delay,
+        }
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff::new()
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn should_retry(
+        &self,
+        retries: u32,
+        method: &Method,
+        failure: &Failure<'_>,
+    ) -> Option<Duration> {
+        if retries >= self.max_retries {
+            return None;
+        }
+        let resendable = self.retry_non_idempotent || method.is_idempotent();
+
+        match failure {
+            // Nothing reached FMC, so any method is safe to resend.
+            Failure::Transport(e) if e.is_connect() => Some(self.delay(retries)),
+            Failure::Transport(_) if resendable => Some(self.delay(retries)),
+            Failure::Status(status, headers) if self.statuses.contains(status) => {
+                // A 429 is rejected before FMC acts on it.
+                if !resendable && *status != StatusCode::TOO_MANY_REQUESTS {
+                    return None;
+                }
+                match retry_after(headers) {
+                    Some(delay) => Some(delay.min(self.max_delay)),
+                    None => Some(self.delay(retries)),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl RetryPolicy for NoRetry {
+    fn should_retry(&self, _: u32, _: &Method, _: &Failure<'_>) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retry-After as delay-seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some(
+        (date - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::from_secs(0)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(code: u16, headers: &HeaderMap) -> Failure<'_> {
+        Failure::Status(StatusCode::from_u16(code).unwrap(), headers)
+    }
+
+    #[test]
+    fn fn_backoff() {
+        let policy = ExponentialBackoff::new().jitter(false).max_retries(10);
+        let headers = HeaderMap::new();
+        let wait =
+            |retries, code| policy.should_retry(retries, &Method::GET, &status(code, &headers));
+
+        assert_eq!(wait(0, 503), Some(Duration::from_millis(500)));
+        assert_eq!(wait(3, 502), Some(Duration::from_secs(4)));
+        assert_eq!(wait(9, 504), Some(DEFAULT_MAX_DELAY));
+        assert_eq!(wait(10, 503), None);
+        assert_eq!(wait(0, 500), None);
+        assert_eq!(wait(0, 404), None);
+
+        let delay = ExponentialBackoff::new().delay(2);
+        assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn fn_idempotency() {
+        let policy = ExponentialBackoff::new().jitter(false);
+        let mut headers = HeaderMap::new();
+
+        assert_eq!(
+            policy.should_retry(0, &Method::POST, &status(503, &headers)),
+            None
+        );
+        assert!(policy
+            .should_retry(0, &Method::PUT, &status(503, &headers))
+            .is_some());
+        assert!(policy
+            .should_retry(0, &Method::POST, &status(429, &headers))
+            .is_some());
+
+        let policy = policy.retry_non_idempotent(true);
+        assert!(policy
+            .should_retry(0, &Method::POST, &status(503, &headers))
+            .is_some());
+
+        headers.insert("retry-after", "7".parse().unwrap());
+        assert_eq!(
+            policy.should_retry(0, &Method::POST, &status(429, &headers)),
+            Some(Duration::from_secs(7))
+        );
+        headers.insert("retry-after", "86400".parse().unwrap());
+        assert_eq!(
+            policy.should_retry(0, &Method::GET, &status(503, &headers)),
+            Some(DEFAULT_MAX_DELAY)
+        );
+        headers.insert(
+            "retry-after",
+            "Tue, 31 Dec 2999 23:59:59 GMT".parse().unwrap(),
+        );
+        assert_eq!(
+            policy.should_retry(0, &Method::GET, &status(503, &headers)),
+            Some(DEFAULT_MAX_DELAY)
+        );
+        headers.insert(
+            "retry-after",
+            "Tue, 02 Jun 2020 17:31:08 GMT".parse().unwrap(),
+        );
+        assert_eq!(
+            policy.should_retry(0, &Method::GET, &status(503, &headers)),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(
+            NoRetry.should_retry(0, &Method::GET, &status(503, &headers)),
+            None
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use super::{fmt, Duration, HeaderMap, Method, StatusCode};
+
+/// Decides whether a failed request is sent again, and when. Set one on
+/// FmcClient::retry_policy() or FmcRequest::retry_policy().
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// How long to wait before resending, or None to give up and return
+    /// the failure. `retries` counts resends so far.
+    fn should_retry(
+        &self,
+        retries: u32,
+        method: &Method,
+        failure: &Failure<'_>,
+    ) -> Option<Duration>;
+}
+
+/// Why an attempt failed.
+#[derive(Debug)]
+pub enum Failure<'a> {
+    /// FMC answered with a non-2xx status.
+    Status(StatusCode, &'a HeaderMap),
+    /// The connection could not be made or broke mid-request.
+    Transport(&'a hyper::Error),
+}
+
+/// The default policy: exponential backoff with jitter, honoring
+/// Retry-After. Only idempotent methods (GET, PUT, DELETE) are resent
+/// after FMC may have acted on the request; POSTs are resent only when
+/// the connection failed or FMC answered 429.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialBackoff {
+    pub(super) max_retries: u32,
+    pub(super) base_delay: Duration,
+    pub(super) max_delay: Duration,
+    pub(super) jitter: bool,
+    pub(super) statuses: Vec<StatusCode>,
+    pub(super) retry_non_idempotent: bool,
+}
+
+/// Sends every request exactly once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoRetry;
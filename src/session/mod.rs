@@ -1,26 +1,43 @@
-use super::*;
-use super::{uri::uri_schema::FmcApi, FMCUri}; // Local
+use super::error::error_schema::FmcError;
+use super::uri::uri_schema::{Filter, FmcApi, FmcQuery};
+use super::FMCUri;
+use super::*; // Local
 
 mod session_methods;
 pub mod session_schema;
 
-async fn collect_body(body: hyper::client::ResponseFuture) -> String {
-    let bytes = hyper::body::to_bytes(body.await.unwrap().body_mut())
-        .await
-        .unwrap(); //ok this works, fix later
-    let text = std::str::from_utf8(&bytes).unwrap(); //handle
+/// FMC refuses a refresh after an access token has been refreshed three times.
+const MAX_TOKEN_REFRESHES: u8 = 3;
 
-    String::from(text)
+/// Refresh the access token this many seconds before it expires by default.
+const DEFAULT_REFRESH_MARGIN: i64 = 300;
+
+/// Reads a whole response body into a String.
+pub async fn collect_body(resp: Response<Body>) -> Result<String, FmcError> {
+    let bytes = hyper::body::to_bytes(resp.into_body()).await?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Passes 2xx responses through and turns everything else into
+/// an FmcError::Status carrying FMC's error body.
+async fn check_status(resp: Response<Body>) -> Result<Response<Body>, FmcError> {
+    if resp.status().is_success() {
+        return Ok(resp);
+    }
+
+    let status = resp.status();
+    let bytes = hyper::body::to_bytes(resp.into_body()).await?;
+    Err(FmcError::from_status(status, &bytes))
 }
 
-async fn hdr_string(hdr: Option<HeaderValue>) -> String {
+async fn hdr_string(hdr: Option<HeaderValue>, name: &'static str) -> Result<String, FmcError> {
     let hdr_val = match hdr {
         Some(s) => s,
-        None => return String::from("Header not found"),
+        None => return Err(FmcError::MissingHeader(name)),
     };
 
     match hdr_val.to_str() {
-        Ok(val_str) => String::from(val_str),
-        Err(e) => String::from("Error parsing header"),
+        Ok(val_str) => Ok(String::from(val_str)),
+        Err(_) => Err(FmcError::BadHeader(name)),
     }
 }
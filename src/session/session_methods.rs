@@ -1,95 +1,203 @@
-use super::HttpsConnector;
+use super::super::domain;
+use super::super::domain::domain_schema::DomainInfo;
+use super::super::info::info_schema::Version as FmcVersion;
+use super::super::limit::limit_schema::RateLimiter;
+use super::super::retry::retry_schema::{ExponentialBackoff, Failure, RetryPolicy};
+use super::super::tls::tls_schema::{FmcConnector, TlsConfig};
 use super::Uuid;
-use super::{Client, HeaderMap, Request, Version}; // From crate: Hyper // From crate: Hyper-TLS
-use super::{DateTime, Duration, FixedOffset, Utc};
+use super::{Body, Client, HeaderMap, HttpsClient, Request, Response, Version}; // From crate: Hyper
+use super::{DateTime, Duration, Utc};
 
-use super::super::json::json_schema::devices;
-use super::{collect_body, hdr_string, session_schema, FMCUri, FmcApi}; // Local
-use session_schema::{FmcRequest, RequestType, SessionCreds}; // Local
+use super::{check_status, hdr_string, session_schema, Filter, FmcApi, FmcError, FmcQuery}; // Local
+use super::{DEFAULT_REFRESH_MARGIN, MAX_TOKEN_REFRESHES};
+use serde::Serialize;
+use session_schema::{FmcRequest, RequestType, SessionCreds, Transport}; // Local
+use std::fmt;
+use std::sync::Arc;
+use tokio::time::delay_for;
 
 impl<'a> FmcRequest<'a> {
-    async fn new() -> FmcRequest<'a> {
+    pub async fn new() -> Result<FmcRequest<'a>, FmcError> {
+        let client = Client::builder().build::<_, Body>(FmcConnector::new()?);
+        let req = FmcRequest::with_session(Transport::new(client), SessionCreds::new().await).await;
+        Ok(FmcRequest {
+            owns_session: true,
+            ..req
+        })
+    }
+
+    /// Starts a request that reuses an existing transport and session.
+    /// build() leaves the token alone; whoever owns the session keeps it
+    /// fresh, as FmcClient::request() does.
+    pub(crate) async fn with_session(
+        transport: Transport,
+        sess_creds: SessionCreds,
+    ) -> FmcRequest<'a> {
         FmcRequest {
+            transport,
             method: None,
             host: None,
+            api: None,
             uri: None,
+            query: FmcQuery::new(),
+            body: None,
             req: None,
             is_new_auth: false,
-            sess_creds: SessionCreds::new().await,
+            sess_creds,
+            domain: None,
+            server_version: None,
+            owns_session: false,
         }
     }
 
-    async fn host(mut self, host: &'a str) -> FmcRequest<'a> {
+    /// Lets method() reject endpoints newer than `version`
+    /// with FmcError::UnsupportedVersion.
+    pub(crate) fn server_version(mut self, version: FmcVersion) -> FmcRequest<'a> {
+        self.server_version = Some(version);
+        self
+    }
+
+    /// Swaps in a hyper client that verifies the FMC per `tls`.
+    pub async fn tls(mut self, tls: &TlsConfig) -> Result<FmcRequest<'a>, FmcError> {
+        self.transport.http = Client::builder().build::<_, Body>(tls.connector()?);
+        Ok(self)
+    }
+
+    /// Decides which failed requests are resent and after how long.
+    /// Defaults to ExponentialBackoff::new(); NoRetry turns retries off.
+    pub async fn retry_policy<P: RetryPolicy + 'static>(mut self, policy: P) -> FmcRequest<'a> {
+        self.transport.retry = Arc::new(policy);
+        self
+    }
+
+    pub async fn host(mut self, host: &'a str) -> FmcRequest<'a> {
         self.host = Some(host);
         self
     }
 
-    async fn get(mut self, req_type: FmcApi) -> FmcRequest<'a> {
-        let api_path = match &self.host {
-            Some(host) => {
-                req_type
-                    .path_string(Some(&host), self.sess_creds.dom_uuid)
-                    .await
+    pub async fn get(self, req_type: FmcApi) -> Result<FmcRequest<'a>, FmcError> {
+        self.method(RequestType::Get, req_type).await
+    }
+
+    pub async fn post(self, req_type: FmcApi) -> Result<FmcRequest<'a>, FmcError> {
+        self.method(RequestType::Post, req_type).await
+    }
+
+    pub async fn put(self, req_type: FmcApi) -> Result<FmcRequest<'a>, FmcError> {
+        self.method(RequestType::Put, req_type).await
+    }
+
+    pub async fn delete(self, req_type: FmcApi) -> Result<FmcRequest<'a>, FmcError> {
+        self.method(RequestType::Delete, req_type).await
+    }
+
+    async fn method(
+        mut self,
+        method: RequestType,
+        req_type: FmcApi,
+    ) -> Result<FmcRequest<'a>, FmcError> {
+        if let (Some(found), Some(required)) = (&self.server_version, req_type.min_version()) {
+            if *found < required {
+                return Err(FmcError::UnsupportedVersion {
+                    endpoint: format!("{:?}", req_type),
+                    required: required.to_string(),
+                    found: found.to_string(),
+                });
             }
-            None => panic!("No host specified!"),
+        }
+
+        self.method = Some(method);
+        self.api = Some(req_type);
+        self.resolve_uri().await?;
+        Ok(self)
+    }
+
+    /// Addresses `domain` (a name or UUID the user can access) instead of
+    /// the session's domain, for this request only.
+    pub async fn domain(mut self, domain: &str) -> Result<FmcRequest<'a>, FmcError> {
+        self.domain = Some(self.sess_creds.resolve_domain(domain)?);
+        if self.api.is_some() {
+            self.resolve_uri().await?;
+        }
+        Ok(self)
+    }
+
+    async fn resolve_uri(&mut self) -> Result<(), FmcError> {
+        let (host, api) = match (&self.host, &self.api) {
+            (Some(host), Some(api)) => (host, api),
+            (None, _) => return Err(FmcError::MissingField("host")),
+            (_, None) => return Err(FmcError::MissingField("method")),
         };
+        let dom_uuid = self.domain.or_else(|| self.sess_creds.current_domain());
+
+        self.uri = Some(api.clone().path_string(Some(host), dom_uuid).await?);
+        Ok(())
+    }
 
-        self.method = Some(RequestType::GET);
-        self.uri = Some(api_path);
+    /// Replaces the whole query string.
+    pub async fn query(mut self, query: FmcQuery) -> FmcRequest<'a> {
+        self.query = query;
         self
     }
-    //pub fn arg<'a>(&'a mut self, arg: String) -> &'a mut Command
-    async fn post(mut self, req_type: FmcApi) -> FmcRequest<'a> {
-        let api_path = match &self.host {
-            Some(host) => {
-                req_type
-                    .path_string(Some(&host), self.sess_creds.dom_uuid)
-                    .await
-            }
-            None => panic!("No host specified!"),
-        };
 
-        self.method = Some(RequestType::POST);
-        self.uri = Some(api_path);
+    pub async fn filter(mut self, filter: Filter) -> FmcRequest<'a> {
+        self.query = self.query.filter(filter);
         self
     }
 
-    async fn put(mut self, req_type: FmcApi) -> FmcRequest<'a> {
-        let api_path = match &self.host {
-            Some(host) => {
-                req_type
-                    .path_string(Some(&host), self.sess_creds.dom_uuid)
-                    .await
-            }
-            None => panic!("No host specified!"),
-        };
-        self.method = Some(RequestType::PUT);
-        self.uri = Some(api_path);
+    pub async fn offset(mut self, offset: u32) -> FmcRequest<'a> {
+        self.query = self.query.offset(offset);
         self
     }
 
-    async fn delete(mut self, req_type: FmcApi) -> FmcRequest<'a> {
-        let api_path = match &self.host {
-            Some(host) => {
-                req_type
-                    .path_string(Some(&host), self.sess_creds.dom_uuid)
-                    .await
-            }
-            None => panic!("No host specified!"),
-        };
-        self.method = Some(RequestType::DELETE);
-        self.uri = Some(api_path);
+    pub async fn limit(mut self, limit: u32) -> FmcRequest<'a> {
+        self.query = self.query.limit(limit);
+        self
+    }
+
+    pub async fn expanded(mut self, expanded: bool) -> FmcRequest<'a> {
+        self.query = self.query.expanded(expanded);
+        self
+    }
+
+    /// Serializes `body` as the JSON request body, for POST and PUT.
+    pub async fn json<T: Serialize>(mut self, body: &T) -> Result<FmcRequest<'a>, FmcError> {
+        self.body = Some(serde_json::to_vec(body).map_err(FmcError::Encode)?);
+        Ok(self)
+    }
+
+    /// Sends `body` as-is. The Content-Type is still application/json.
+    pub async fn raw_body(mut self, body: Vec<u8>) -> FmcRequest<'a> {
+        self.body = Some(body);
         self
     }
 
-    async fn http_basic(mut self, username: &str, password: &str) -> FmcRequest<'a> {
-        self.sess_creds.http_basic_auth(username, password);
+    pub async fn http_basic(mut self, username: &str, password: &str) -> FmcRequest<'a> {
+        self.sess_creds.http_basic_auth(username, password).await;
         self.is_new_auth = true;
         self
     }
 
-    async fn build(mut self) -> FmcRequest<'a> {
-        let uri = self.uri.clone().unwrap();
+    /// Sets how close to expiry (in seconds) the access token may get
+    /// before build() refreshes it.
+    pub async fn refresh_margin(mut self, seconds: i64) -> FmcRequest<'a> {
+        self.sess_creds.refresh_margin = Duration::seconds(seconds);
+        self
+    }
+
+    pub async fn build(mut self) -> Result<FmcRequest<'a>, FmcError> {
+        let mut uri = match &self.uri {
+            Some(uri) => uri.clone(),
+            None => return Err(FmcError::MissingField("method")),
+        };
+
+        if !self.query.is_empty() {
+            let query = match &self.api {
+                Some(api) => self.query.render(api)?,
+                None => return Err(FmcError::MissingField("method")),
+            };
+            uri = [uri, query].join("?");
+        }
 
         let mut req = Request::builder()
             .uri(uri)
@@ -97,74 +205,200 @@ impl<'a> FmcRequest<'a> {
             .header("Content-Type", "application/json");
 
         req = match &self.method {
-            Some(RequestType::GET) => req.method("GET"),
-            Some(RequestType::POST) => req.method("POST"),
-            Some(RequestType::PUT) => req.method("PUT"),
-            Some(RequestType::DELETE) => req.method("DELETE"),
-            None => panic!("No method defined"), //handle!
+            Some(RequestType::Get) => req.method("GET"),
+            Some(RequestType::Post) => req.method("POST"),
+            Some(RequestType::Put) => req.method("PUT"),
+            Some(RequestType::Delete) => req.method("DELETE"),
+            None => return Err(FmcError::MissingField("method")),
         };
 
         req = match &self.is_new_auth {
             true => {
-                let b64_string = self.sess_creds.api_basic_auth.clone().unwrap();
-                req.header("Authorization", ["Basic", &b64_string].join(" "))
+                let b64_string = match &self.sess_creds.api_basic_auth {
+                    Some(b64_string) => b64_string,
+                    None => return Err(FmcError::MissingField("credentials")),
+                };
+                req.header("Authorization", ["Basic", b64_string].join(" "))
             }
             false => {
-                let token_string = self.sess_creds.xa_token.clone().unwrap();
+                let host = match self.host {
+                    Some(host) => host,
+                    None => return Err(FmcError::MissingField("host")),
+                };
+                if self.owns_session {
+                    self.sess_creds
+                        .refresh_if_expiring(&self.transport, host)
+                        .await?;
+                }
+
+                let token_string = match &self.sess_creds.xa_token {
+                    Some(token_string) => token_string,
+                    None => return Err(FmcError::MissingField("access token")),
+                };
                 req.header("X-Auth-Access-Token", token_string)
             }
         };
 
-        let req = req.body(hyper::Body::empty()).unwrap(); // FIX
-
-        self.req = Some(req);
-        self
+        let body = match self.body.clone() {
+            Some(bytes) => Body::from(bytes),
+            None => Body::empty(),
+        };
+        self.req = Some(req.body(body)?);
+        Ok(self)
     }
 
     /// Terminates the builder chain by sending the
     /// request, consuming the struct in the process.
     /// Useful for one-off requests when you have the token.
-    async fn send(self) -> hyper::client::ResponseFuture {
-        let https = HttpsConnector::new();
-        let client = Client::builder().build::<_, hyper::Body>(https);
-        client.request(self.req.unwrap()) //handle
+    pub async fn send(mut self) -> Result<Response<Body>, FmcError> {
+        let req = match self.req.take() {
+            Some(req) => req,
+            None => return Err(FmcError::MissingField("build")),
+        };
+
+        self.transport.send(req, self.body.as_deref()).await
     }
 
     /// Terminates the builder chain by sending the request, yielding
     /// a response and new struct with authentication credentials intact.
     /// Deconstruct with let (resp, req) = FmcRequest::...
     /// Useful series of requests while retaining authentication credentials.
-    async fn next(mut self) -> (hyper::body::Body, FmcRequest<'a>) {
-        let https = HttpsConnector::new();
-        let client = Client::builder().build::<_, hyper::Body>(https);
+    pub async fn next(mut self) -> Result<(Body, FmcRequest<'a>), FmcError> {
+        let req = match self.req.take() {
+            Some(req) => req,
+            None => return Err(FmcError::MissingField("build")),
+        };
 
-        let resp = client.request(self.req.unwrap()); //handle error
-        let (resp_headers, resp_body) = resp.await.unwrap().into_parts();
+        let resp = self.transport.send(req, self.body.as_deref()).await?;
+        let (resp_headers, resp_body) = resp.into_parts();
         let mut resp_headers = resp_headers.headers;
 
-        if self.is_new_auth {}
-        self.sess_creds.record_tokens(&mut resp_headers).await;
-        (
+        if self.is_new_auth {
+            self.sess_creds.record_tokens(&mut resp_headers).await?;
+            self.sess_creds.refresh_count = 0;
+        }
+        Ok((
             resp_body,
             FmcRequest {
+                transport: self.transport,
                 method: None,
                 host: self.host,
+                api: None,
                 uri: None,
+                query: FmcQuery::new(),
+                body: None,
                 req: None,
                 is_new_auth: false,
                 sess_creds: self.sess_creds,
+                domain: None,
+                server_version: self.server_version,
+                owns_session: self.owns_session,
             },
-        )
+        ))
     }
 
     /// Terminates the builder chain and stores the fiendish creations
-    async fn store(self) -> FmcRequest<'a> {
+    pub async fn store(self) -> FmcRequest<'a> {
         self
     }
 }
 
-impl<'a> SessionCreds {
-    async fn new() -> Self {
+impl Transport {
+    pub(crate) fn new(http: HttpsClient) -> Transport {
+        Transport {
+            http,
+            limiter: None,
+            retry: Arc::new(ExponentialBackoff::new()),
+        }
+    }
+
+    /// Paces every request through a limiter shared with other requests.
+    pub(crate) fn limiter(mut self, limiter: Arc<RateLimiter>) -> Transport {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Sends `req` with `body` (hyper bodies can't be replayed, so the
+    /// bytes are passed separately), waiting for the rate limiter and
+    /// resending for as long as the retry policy asks to.
+    pub(crate) async fn send(
+        &self,
+        req: Request<Body>,
+        body: Option<&[u8]>,
+    ) -> Result<Response<Body>, FmcError> {
+        let (parts, _) = req.into_parts();
+        let mut retries = 0;
+        loop {
+            let mut req = Request::new(match body {
+                Some(bytes) => Body::from(bytes.to_vec()),
+                None => Body::empty(),
+            });
+            *req.method_mut() = parts.method.clone();
+            *req.uri_mut() = parts.uri.clone();
+            *req.version_mut() = parts.version;
+            *req.headers_mut() = parts.headers.clone();
+
+            let resp = match &self.limiter {
+                // The body is read before the permit drops, so a page still
+                // downloading counts against the connection cap.
+                Some(limiter) => {
+                    let _permit = limiter.acquire().await;
+                    match self.http.request(req).await {
+                        Ok(resp) => buffered(resp).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                None => self.http.request(req).await,
+            };
+
+            let failure = match &resp {
+                Ok(resp) if resp.status().is_success() => None,
+                Ok(resp) => Some(Failure::Status(resp.status(), resp.headers())),
+                Err(e) => Some(Failure::Transport(e)),
+            };
+            let wait = failure
+                .and_then(|failure| self.retry.should_retry(retries, &parts.method, &failure));
+
+            match wait {
+                Some(wait) => {
+                    delay_for(wait).await;
+                    retries += 1;
+                }
+                None => return check_status(resp?).await,
+            }
+        }
+    }
+}
+
+/// The same response with its body already read into memory.
+async fn buffered(resp: Response<Body>) -> Result<Response<Body>, hyper::Error> {
+    let (parts, body) = resp.into_parts();
+    let bytes = hyper::body::to_bytes(body).await?;
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+impl fmt::Debug for SessionCreds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("SessionCreds")
+            .field("api_username", &self.api_username)
+            .field("api_password", &redacted(&self.api_password))
+            .field("api_basic_auth", &redacted(&self.api_basic_auth))
+            .field("xa_token", &redacted(&self.xa_token))
+            .field("xar_token", &redacted(&self.xar_token))
+            .field("dom_uuid", &self.dom_uuid)
+            .field("domains", &self.domains)
+            .field("domain", &self.domain)
+            .field("token_issue_time", &self.token_issue_time)
+            .field("token_expires", &self.token_expires)
+            .field("refresh_count", &self.refresh_count)
+            .field("refresh_margin", &self.refresh_margin)
+            .finish()
+    }
+}
+
+impl SessionCreds {
+    pub async fn new() -> Self {
         SessionCreds {
             api_username: None,
             api_password: None,
@@ -172,13 +406,17 @@ impl<'a> SessionCreds {
             xa_token: None,
             xar_token: None,
             dom_uuid: None,
+            domains: Vec::new(),
+            domain: None,
             token_issue_time: None,
             token_expires: None,
+            refresh_count: 0,
+            refresh_margin: Duration::seconds(DEFAULT_REFRESH_MARGIN),
         }
     }
 
-    async fn http_basic_auth(&mut self, username: &str, password: &str) {
-        let auth_str = vec![username, ":", password].join("");
+    pub async fn http_basic_auth(&mut self, username: &str, password: &str) {
+        let auth_str = [username, ":", password].join("");
         let auth_b64 = base64::encode(auth_str.as_bytes());
 
         self.api_username = Some(username.to_string());
@@ -186,17 +424,34 @@ impl<'a> SessionCreds {
         self.api_basic_auth = Some(auth_b64);
     }
 
-    async fn record_tokens(&mut self, headers: &mut HeaderMap) {
-        let xa_token = hdr_string(headers.remove("x-auth-access-token")).await;
-        let xar_token = hdr_string(headers.remove("x-auth-refresh-token")).await;
-
-        let dom_uuid_str = hdr_string(headers.remove("domain_uuid")).await;
-        let dom_uuid = Uuid::parse_str(&dom_uuid_str).unwrap();
+    async fn record_tokens(&mut self, headers: &mut HeaderMap) -> Result<(), FmcError> {
+        let xa_token =
+            hdr_string(headers.remove("x-auth-access-token"), "X-auth-access-token").await?;
+        let xar_token = hdr_string(
+            headers.remove("x-auth-refresh-token"),
+            "X-auth-refresh-token",
+        )
+        .await?;
+
+        let dom_uuid_str = hdr_string(headers.remove("domain_uuid"), "DOMAIN_UUID").await?;
+        let dom_uuid = Uuid::parse_str(&dom_uuid_str)?;
+
+        // Lists every domain the user can access, as [{"name", "uuid"}].
+        // Refresh replies may leave it out; the login's list still holds.
+        let domains = match headers.remove("domains") {
+            Some(hdr) => {
+                let json = hdr_string(Some(hdr), "DOMAINS").await?;
+                let domains = serde_json::from_str(&json);
+                Some(domains.map_err(|_| FmcError::BadHeader("DOMAINS"))?)
+            }
+            None => None,
+        };
 
-        let time_str = hdr_string(headers.remove("date")).await;
-        let time = DateTime::parse_from_rfc2822(&time_str)
-            .unwrap()
-            .with_timezone(&Utc);
+        let time_str = hdr_string(headers.remove("date"), "Date").await?;
+        let time = match DateTime::parse_from_rfc2822(&time_str) {
+            Ok(time) => time.with_timezone(&Utc),
+            Err(_) => return Err(FmcError::BadHeader("Date")),
+        };
 
         let thirty_mins = Duration::minutes(30);
         let expiry = time + thirty_mins;
@@ -204,53 +459,329 @@ impl<'a> SessionCreds {
         self.xa_token = Some(xa_token);
         self.xar_token = Some(xar_token);
         self.dom_uuid = Some(dom_uuid);
+        if let Some(domains) = domains {
+            self.domains = domains;
+        }
         self.token_issue_time = Some(time);
         self.token_expires = Some(expiry);
+        Ok(())
+    }
+
+    /// The domain UUID FMC assigned at login.
+    pub fn dom_uuid(&self) -> Option<Uuid> {
+        self.dom_uuid
     }
 
-    fn token_age(&self) -> i64 {
+    /// Every domain the user can access, as listed at login.
+    pub fn domains(&self) -> &[DomainInfo] {
+        &self.domains
+    }
+
+    /// The domain requests address: the one chosen with
+    /// FmcClient::use_domain(), or else the login domain.
+    pub fn current_domain(&self) -> Option<Uuid> {
+        self.domain.or(self.dom_uuid)
+    }
+
+    /// Looks up a domain by UUID, full name ("Global/Tenant-A") or
+    /// unambiguous short name ("Tenant-A").
+    pub fn resolve_domain(&self, domain: &str) -> Result<Uuid, FmcError> {
+        domain::resolve(&self.domains, domain)
+    }
+
+    pub(crate) fn select_domain(&mut self, domain: Uuid) {
+        self.domain = Some(domain);
+    }
+
+    /// Seconds until the access token expires.
+    pub fn token_age(&self) -> i64 {
         let right_now: DateTime<Utc> = Utc::now();
-        let remainder = self.token_expires.unwrap() - right_now;
-        Duration::num_seconds(&remainder)
+        match self.token_expires {
+            Some(expires) => Duration::num_seconds(&(expires - right_now)),
+            None => 0,
+        }
+    }
+
+    fn needs_refresh(&self) -> bool {
+        self.xa_token.is_some() && self.token_age() <= self.refresh_margin.num_seconds()
+    }
+
+    /// Refreshes the access token if it expires within the refresh margin.
+    /// Once FMC's refresh limit is used up, a new token is generated
+    /// from the stored username and password instead.
+    pub(crate) async fn refresh_if_expiring(
+        &mut self,
+        transport: &Transport,
+        host: &str,
+    ) -> Result<(), FmcError> {
+        if !self.needs_refresh() {
+            return Ok(());
+        }
+
+        if self.refresh_count < MAX_TOKEN_REFRESHES {
+            self.refresh_token(transport, host).await
+        } else {
+            self.generate_token(transport, host).await
+        }
+    }
+
+    /// Logs in if there is no access token yet, otherwise refreshes
+    /// it when it is close to expiring.
+    pub(crate) async fn ensure_token(
+        &mut self,
+        transport: &Transport,
+        host: &str,
+    ) -> Result<(), FmcError> {
+        match self.xa_token {
+            Some(_) => self.refresh_if_expiring(transport, host).await,
+            None => self.generate_token(transport, host).await,
+        }
+    }
+
+    async fn refresh_token(&mut self, transport: &Transport, host: &str) -> Result<(), FmcError> {
+        let uri = FmcApi::RefreshToken
+            .path_string(Some(host), self.dom_uuid)
+            .await?;
+
+        let (xa_token, xar_token) = match (&self.xa_token, &self.xar_token) {
+            (Some(xa_token), Some(xar_token)) => (xa_token, xar_token),
+            _ => return Err(FmcError::MissingField("refresh token")),
+        };
+
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .version(Version::HTTP_11)
+            .header("Content-Type", "application/json")
+            .header("X-auth-access-token", xa_token)
+            .header("X-auth-refresh-token", xar_token)
+            .body(Body::empty())?;
+
+        self.request_tokens(transport, req).await?;
+        self.refresh_count += 1;
+        Ok(())
+    }
+
+    async fn generate_token(&mut self, transport: &Transport, host: &str) -> Result<(), FmcError> {
+        let uri = FmcApi::HttpBasicAuth.path_string(Some(host), None).await?;
+        let b64_string = match &self.api_basic_auth {
+            Some(b64_string) => b64_string,
+            None => return Err(FmcError::MissingField("credentials")),
+        };
+
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .version(Version::HTTP_11)
+            .header("Content-Type", "application/json")
+            .header("Authorization", ["Basic", b64_string].join(" "))
+            .body(Body::empty())?;
+
+        self.request_tokens(transport, req).await?;
+        self.refresh_count = 0;
+        Ok(())
+    }
+
+    async fn request_tokens(
+        &mut self,
+        transport: &Transport,
+        req: Request<Body>,
+    ) -> Result<(), FmcError> {
+        let resp = transport.send(req, None).await?;
+        let (resp_headers, _) = resp.into_parts();
+        let mut resp_headers = resp_headers.headers;
+
+        self.record_tokens(&mut resp_headers).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::Uuid;
-    use super::*; // From crate: Uuid
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::super::super::mock::{MOCK_DOMAIN_UUID, MOCK_PASSWORD, MOCK_USERNAME};
+    use super::super::super::uri::uri_schema::Object;
+    use super::*;
 
     #[tokio::test]
-    async fn fn_new_request() {
-        let (resp, new_req) = FmcRequest::new()
-            .await
-            .host("10.17.11.151")
+    async fn fn_new_request() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let host = mock.url();
+
+        let (_resp, new_req) = FmcRequest::new()
+            .await?
+            .host(&host)
             .await
             .post(FmcApi::HttpBasicAuth)
-            .await
-            .http_basic("apiuser", "vZZ90-8D1z")
+            .await?
+            .http_basic(MOCK_USERNAME, MOCK_PASSWORD)
             .await
             .build()
-            .await
+            .await?
             .next()
-            .await;
+            .await?;
+
+        assert_eq!(
+            new_req.sess_creds.dom_uuid().unwrap().to_string(),
+            MOCK_DOMAIN_UUID
+        );
+        assert!(new_req.sess_creds.token_age() > 29 * 60);
+        assert!(!new_req.is_new_auth);
+        Ok(())
+    }
 
-        println!("{:#?}", &new_req);
+    #[tokio::test]
+    async fn fn_refresh_limit() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let host = mock.url();
+        let transport = FmcRequest::new().await?.transport;
+
+        let mut creds = SessionCreds::new().await;
+        creds.http_basic_auth(MOCK_USERNAME, MOCK_PASSWORD).await;
+        creds.ensure_token(&transport, &host).await?;
+
+        // Force a refresh on every check: three refreshes, then a new login.
+        creds.refresh_margin = Duration::minutes(31);
+        for _ in 0..4 {
+            creds.ensure_token(&transport, &host).await?;
+        }
+        assert_eq!(creds.refresh_count, 0);
+
+        let logins = mock
+            .requests()
+            .iter()
+            .filter(|r| r.ends_with("/generatetoken"))
+            .count();
+        assert_eq!(logins, 2);
+        Ok(())
+    }
 
-        println!("{:#?}", new_req.sess_creds.token_age());
-        // let resp = new_req
-        //     .get(FmcApi::Devices)
-        //     .await
-        //     .xauth_access_token(None)
-        //     .await
-        //     .build()
-        //     .await
-        //     .send()
-        //     .await;
+    #[tokio::test]
+    async fn fn_build_query() -> Result<(), FmcError> {
+        let mut sess_creds = SessionCreds::new().await;
+        sess_creds.xa_token = Some("token".to_string());
+        sess_creds.token_expires = Some(Utc::now() + Duration::minutes(30));
+        sess_creds.dom_uuid =
+            Some(Uuid::parse_str("e276abec-e0f2-11e3-8169-6d9ed49b625f").unwrap());
+
+        let req = FmcRequest::new().await?.host("fmc.local").await;
+        let req = FmcRequest { sess_creds, ..req }
+            .get(FmcApi::Devices)
+            .await?
+            .filter(Filter::Name("fw-01".to_string()))
+            .await
+            .limit(1000)
+            .await
+            .build()
+            .await?;
+
+        assert_eq!(
+            req.req.unwrap().uri().to_string(),
+            "https://fmc.local:443/api/fmc_config/v1/domain/e276abec-e0f2-11e3-8169-6d9ed49b625f\
+             /devices/devicerecords?filter=name%3Afw-01&limit=1000"
+        );
+        Ok(())
+    }
 
-        // let body = collect_body(resp).await;
+    #[tokio::test]
+    async fn fn_build_json_body() -> Result<(), FmcError> {
+        let mut sess_creds = SessionCreds::new().await;
+        sess_creds.xa_token = Some("token".to_string());
+        sess_creds.token_expires = Some(Utc::now() + Duration::minutes(30));
+
+        let req = FmcRequest::new().await?.host("fmc.local").await;
+        let req = FmcRequest { sess_creds, ..req }
+            .post(FmcApi::Objects(Object::Hosts(None)))
+            .await?
+            .json(&serde_json::json!({"name": "web-01", "value": "10.0.0.10"}))
+            .await?
+            .build()
+            .await?;
+
+        let body = hyper::body::to_bytes(req.req.unwrap().into_body()).await?;
+        assert_eq!(&body[..], br#"{"name":"web-01","value":"10.0.0.10"}"#);
+
+        let mut bad = std::collections::BTreeMap::new();
+        bad.insert(vec![1u8], 1u8);
+        let err = FmcRequest::new().await?.json(&bad).await;
+        assert!(matches!(err, Err(FmcError::Encode(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fn_missing_host() {
+        let err = FmcRequest::new().await.unwrap().get(FmcApi::Devices).await;
+        assert!(matches!(err, Err(FmcError::MissingField("host"))));
+    }
+
+    #[tokio::test]
+    async fn fn_record_tokens() {
+        let mut creds = SessionCreds::new().await;
+        let mut headers = HeaderMap::new();
+        headers.insert("x-auth-access-token", "abc".parse().unwrap());
+        headers.insert("x-auth-refresh-token", "def".parse().unwrap());
+        headers.insert("domain_uuid", "not-a-uuid".parse().unwrap());
+        headers.insert("date", "Tue, 02 Jun 2020 17:31:08 GMT".parse().unwrap());
+
+        let err = creds.record_tokens(&mut headers.clone()).await;
+        assert!(matches!(err, Err(FmcError::BadDomainUuid(_))));
+
+        headers.insert(
+            "domain_uuid",
+            "e276abec-e0f2-11e3-8169-6d9ed49b625f".parse().unwrap(),
+        );
+        headers.remove("x-auth-refresh-token");
+        let err = creds.record_tokens(&mut headers.clone()).await;
+        assert!(matches!(
+            err,
+            Err(FmcError::MissingHeader("X-auth-refresh-token"))
+        ));
+
+        headers.insert("x-auth-refresh-token", "def".parse().unwrap());
+        headers.insert("domains", "[{\"name\": \"Global\"}]".parse().unwrap());
+        let err = creds.record_tokens(&mut headers.clone()).await;
+        assert!(matches!(err, Err(FmcError::BadHeader("DOMAINS"))));
+
+        let domains = r#"[{"name": "Global", "uuid": "e276abec-e0f2-11e3-8169-6d9ed49b625f"}]"#;
+        headers.insert("domains", domains.parse().unwrap());
+        creds.record_tokens(&mut headers.clone()).await.unwrap();
+        assert_eq!(creds.domains()[0].name, "Global");
+        assert_eq!(creds.current_domain(), creds.dom_uuid());
+
+        // A refresh reply without DOMAINS keeps the login's list.
+        headers.remove("domains");
+        creds.record_tokens(&mut headers).await.unwrap();
+        assert_eq!(creds.domains().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fn_needs_refresh() {
+        let mut creds = SessionCreds::new().await;
+        assert!(!creds.needs_refresh());
+
+        creds.xa_token = Some("token".to_string());
+        creds.token_expires = Some(Utc::now() + Duration::minutes(30));
+        assert!(!creds.needs_refresh());
+
+        creds.token_expires = Some(Utc::now() + Duration::minutes(4));
+        assert!(creds.needs_refresh());
 
-        // let json: devices::DeviceRecords = serde_json::from_str(&body).unwrap();
-        // println!("{:#?}", json);
+        creds.refresh_margin = Duration::minutes(1);
+        assert!(!creds.needs_refresh());
+
+        creds.token_expires = Some(Utc::now() - Duration::minutes(1));
+        assert!(creds.needs_refresh());
+    }
+
+    #[tokio::test]
+    async fn fn_debug_redacts() {
+        let mut creds = SessionCreds::new().await;
+        creds.http_basic_auth("api-user", "s3cret-pw").await;
+        creds.xa_token = Some("token-abc".to_string());
+
+        let debug = format!("{:?}", creds);
+        assert!(debug.contains("api-user"));
+        assert!(!debug.contains("s3cret-pw"));
+        assert!(!debug.contains("token-abc"));
+        assert!(!debug.contains(creds.api_basic_auth.as_deref().unwrap()));
     }
 }
@@ -1,33 +1,69 @@
+use super::super::domain::domain_schema::DomainInfo;
+use super::super::info::info_schema::Version;
+use super::super::limit::limit_schema::RateLimiter;
+use super::super::retry::retry_schema::RetryPolicy;
 use super::Uuid;
-use super::{DateTime, Utc};
-use super::{FMCUri, Request}; // Local
+use super::{DateTime, Duration, Utc};
+use super::{FMCUri, FmcApi, FmcQuery, HttpsClient, Request}; // Local
+use std::sync::Arc;
 
 #[derive(Debug)]
-pub(super) enum RequestType {
-    GET,
-    POST,
-    PUT,
-    DELETE,
+pub(crate) enum RequestType {
+    Get,
+    Post,
+    Put,
+    Delete,
 }
 
+/// Low-level request builder. Each step consumes and returns the
+/// request; FmcClient wraps the common chains.
 #[derive(Debug)]
-pub(super) struct FmcRequest<'a> {
+pub struct FmcRequest<'a> {
+    pub(super) transport: Transport,
     pub(super) method: Option<RequestType>,
     pub(super) host: Option<&'a str>,
+    pub(super) api: Option<FmcApi>,
     pub(super) uri: Option<FMCUri>,
+    pub(super) query: FmcQuery,
+    pub(super) body: Option<Vec<u8>>,
     pub(super) req: Option<Request<hyper::Body>>,
     pub(super) is_new_auth: bool,
     pub(super) sess_creds: SessionCreds,
+    /// Overrides the session's domain for this request only.
+    pub(super) domain: Option<Uuid>,
+    /// The FMC's version, when known; method() refuses newer endpoints.
+    pub(super) server_version: Option<Version>,
+    /// Whether build() may refresh the token. False for requests from
+    /// FmcClient, which carry a copy of the client's session: the client
+    /// refreshes its own session in request(), so a refresh here would
+    /// be lost with the copy.
+    pub(super) owns_session: bool,
 }
 
-#[derive(Debug)]
-pub(super) struct SessionCreds {
+/// The hyper client plus the pacing and retry rules that every request,
+/// token requests included, is sent through.
+#[derive(Debug, Clone)]
+pub(crate) struct Transport {
+    pub(crate) http: HttpsClient,
+    pub(crate) limiter: Option<Arc<RateLimiter>>,
+    pub(crate) retry: Arc<dyn RetryPolicy>,
+}
+
+/// Credentials and tokens for one FMC login. Debug output leaves out
+/// the password and tokens.
+#[derive(Clone)]
+pub struct SessionCreds {
     pub(super) api_username: Option<String>,
     pub(super) api_password: Option<String>,
     pub(super) api_basic_auth: Option<String>,
     pub(super) xa_token: Option<String>,
     pub(super) xar_token: Option<String>,
     pub(super) dom_uuid: Option<Uuid>,
+    pub(super) domains: Vec<DomainInfo>,
+    /// Domain chosen with FmcClient::use_domain(), instead of dom_uuid.
+    pub(super) domain: Option<Uuid>,
     pub(super) token_issue_time: Option<DateTime<Utc>>,
     pub(super) token_expires: Option<DateTime<Utc>>,
+    pub(super) refresh_count: u8,
+    pub(super) refresh_margin: Duration,
 }
@@ -0,0 +1,11 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra, Reference};
+use super::objects::objects_schema::ObjectList;
+use super::tid::tid_schema::{SourceType, TidSource};
+use super::uri::uri_schema::{FmcApi, FmcQuery, TaxiiResource};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+mod taxii_methods;
+pub mod taxii_schema;
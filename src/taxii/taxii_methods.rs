@@ -0,0 +1,289 @@
+use super::taxii_schema::TaxiiVersion;
+use super::taxii_schema::{Taxii, TaxiiCollection, TaxiiDiscovery, TaxiiHandle, TaxiiObject};
+use super::{Extra, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator, Reference};
+use super::{SourceType, TaxiiResource, TidSource};
+use serde_json::json;
+use std::fmt;
+use std::marker::PhantomData;
+
+impl FmcClient {
+    pub fn taxii(&self) -> Taxii<'_> {
+        Taxii { client: self }
+    }
+}
+
+impl<'c> Taxii<'c> {
+    pub fn discoveries(&self) -> TaxiiHandle<'c, TaxiiDiscovery> {
+        self.handle()
+    }
+
+    pub fn collections(&self) -> TaxiiHandle<'c, TaxiiCollection> {
+        self.handle()
+    }
+
+    /// Saves `collection` if it has no ID yet, then adds a TID source
+    /// that FMC refreshes from it every `refresh` minutes.
+    pub async fn onboard(
+        &self,
+        collection: &TaxiiCollection,
+        refresh: u32,
+    ) -> Result<TidSource, FmcError> {
+        let saved = match collection.id {
+            Some(_) => collection.clone(),
+            None => self.collections().create(collection).await?,
+        };
+        self.client
+            .tid()
+            .sources()
+            .create(&saved.source(refresh))
+            .await
+    }
+
+    fn handle<T: TaxiiObject>(&self) -> TaxiiHandle<'c, T> {
+        TaxiiHandle {
+            client: self.client,
+            record: PhantomData,
+        }
+    }
+}
+
+impl<'c, T: TaxiiObject + 'c> TaxiiHandle<'c, T> {
+    /// Every record of this type, expanded: summaries leave out the URL
+    /// and version every record needs.
+    pub fn list(&self) -> Paginator<'c, ObjectList<T>> {
+        self.client
+            .paginate(FmcApi::TaxiiResources(T::path(None)))
+            .expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<T, FmcError> {
+        self.client
+            .get_json(FmcApi::TaxiiResources(T::path(Some(id.to_string()))))
+            .await
+    }
+
+    pub async fn create(&self, record: &T) -> Result<T, FmcError> {
+        self.client
+            .post_json(
+                FmcApi::TaxiiResources(T::path(None)),
+                FmcQuery::new(),
+                record,
+            )
+            .await
+    }
+
+    /// Replaces the record with the same ID.
+    pub async fn update(&self, record: &T) -> Result<T, FmcError> {
+        let id = match record.id() {
+            Some(id) => id.to_string(),
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(FmcApi::TaxiiResources(T::path(Some(id))), record)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<T, FmcError> {
+        self.client
+            .delete_json(FmcApi::TaxiiResources(T::path(Some(id.to_string()))))
+            .await
+    }
+}
+
+impl TaxiiDiscovery {
+    /// `url` is the discovery service (1.1) or discovery endpoint (2.1).
+    pub fn new(name: &str, url: &str, version: TaxiiVersion) -> TaxiiDiscovery {
+        TaxiiDiscovery {
+            id: None,
+            _type: None,
+            name: name.to_string(),
+            url: url.to_string(),
+            version,
+            username: None,
+            password: None,
+            links: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// HTTP Basic credentials for the TAXII server.
+    pub fn credentials(mut self, username: &str, password: &str) -> TaxiiDiscovery {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+}
+
+impl fmt::Debug for TaxiiDiscovery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaxiiDiscovery")
+            .field("id", &self.id)
+            .field("_type", &self._type)
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("version", &self.version)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("links", &self.links)
+            .field("extra", &self.extra)
+            .finish()
+    }
+}
+
+impl TaxiiCollection {
+    /// A collection on the server `discovery` points at, not yet saved.
+    /// `url` is the 1.1 poll service or 2.1 objects URL; the name starts
+    /// out as the collection ID.
+    pub fn new(discovery: &TaxiiDiscovery, collection_id: &str, url: &str) -> TaxiiCollection {
+        TaxiiCollection {
+            id: None,
+            _type: None,
+            name: collection_id.to_string(),
+            collection_id: collection_id.to_string(),
+            description: None,
+            url: url.to_string(),
+            version: discovery.version.clone(),
+            discovery: discovery.id.as_ref().map(|id| Reference {
+                id: id.clone(),
+                _type: discovery._type.clone(),
+                name: Some(discovery.name.clone()),
+            }),
+            links: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// A TID source FMC refreshes from this collection every `refresh`
+    /// minutes. Links back to the saved collection when it has an ID.
+    pub fn source(&self, refresh: u32) -> TidSource {
+        let mut source = TidSource::url(&self.name, SourceType::Taxii, &self.url, refresh);
+        source.description = self.description.clone();
+        source.extra.insert(
+            "collectionId".to_string(),
+            self.collection_id.clone().into(),
+        );
+        if let Some(id) = &self.id {
+            let mut saved = json!({"id": id, "name": self.name});
+            if let Some(_type) = &self._type {
+                saved["type"] = json!(_type);
+            }
+            source.extra.insert("taxiiCollection".to_string(), saved);
+        }
+        source
+    }
+}
+
+impl TaxiiObject for TaxiiDiscovery {
+    fn path(id: Option<String>) -> TaxiiResource {
+        TaxiiResource::Discoveries(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+impl TaxiiObject for TaxiiCollection {
+    fn path(id: Option<String>) -> TaxiiResource {
+        TaxiiResource::Collections(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+impl From<String> for TaxiiVersion {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "1.1" => TaxiiVersion::V11,
+            "2.1" => TaxiiVersion::V21,
+            _ => TaxiiVersion::Other(value),
+        }
+    }
+}
+
+impl From<TaxiiVersion> for String {
+    fn from(value: TaxiiVersion) -> Self {
+        match value {
+            TaxiiVersion::V11 => "1.1".to_string(),
+            TaxiiVersion::V21 => "2.1".to_string(),
+            TaxiiVersion::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::super::super::mock_taxii::mock_taxii_schema::{MockTaxii, TaxiiProbe};
+    use super::*;
+
+    #[test]
+    fn fn_debug_redacts() {
+        let discovery =
+            TaxiiDiscovery::new("lab", "https://taxii.example/taxii2/", TaxiiVersion::V21)
+                .credentials("feed-user", "feed-s3cret");
+        let debug = format!("{:?}", discovery);
+        assert!(debug.contains("feed-user"));
+        assert!(!debug.contains("feed-s3cret"));
+    }
+
+    #[tokio::test]
+    async fn fn_onboard_feed() -> Result<(), FmcError> {
+        let taxii = MockTaxii::start().await;
+        taxii.collection(
+            "pipeline-ips",
+            "Pipeline IPs",
+            vec![json!({"type": "indicator"})],
+        );
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+
+        for version in &[TaxiiVersion::V21, TaxiiVersion::V11] {
+            let discovery =
+                TaxiiDiscovery::new("lab", &taxii.discovery_url(version), version.clone());
+            let discovery = client.taxii().discoveries().create(&discovery).await?;
+
+            let found = TaxiiProbe::new(&discovery)?.collections().await?;
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].collection_id, "pipeline-ips");
+            assert_eq!(
+                found[0].discovery.as_ref().map(|d| d.id.as_str()),
+                discovery.id.as_deref()
+            );
+            assert!(found[0].url.starts_with(&taxii.url()));
+
+            let source = client.taxii().onboard(&found[0], 60).await?;
+            assert_eq!(source._type, SourceType::Taxii);
+            assert_eq!(source.uri.as_deref(), Some(found[0].url.as_str()));
+            assert!(source.extra.contains_key("taxiiCollection"));
+        }
+        let stored = client.taxii().collections().list().collect_all().await?;
+        assert_eq!(stored.len(), 2);
+        assert!(stored[0]
+            .url
+            .ends_with("/collections/pipeline-ips/objects/"));
+        assert!(stored[1].url.ends_with("/taxii-poll-service"));
+        assert!(taxii
+            .requests()
+            .iter()
+            .any(|r| r == "POST /taxii-collection-management-service"));
+
+        let locked = MockTaxii::start().await;
+        locked.require_credentials("feeduser", "feedpass");
+        let discovery = TaxiiDiscovery::new(
+            "locked",
+            &locked.discovery_url(&TaxiiVersion::V21),
+            TaxiiVersion::V21,
+        );
+        let err = TaxiiProbe::new(&discovery)?
+            .collections()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(|s| s.as_u16()), Some(401));
+        let discovery = discovery.credentials("feeduser", "feedpass");
+        assert!(TaxiiProbe::new(&discovery)?.collections().await?.is_empty());
+        Ok(())
+    }
+}
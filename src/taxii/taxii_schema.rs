@@ -0,0 +1,78 @@
+use super::{core, DeserializeOwned, Extra, FmcClient, Reference, TaxiiResource};
+use super::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// A record type under /taxiiconfig, e.g. TaxiiCollection.
+pub trait TaxiiObject: Serialize + DeserializeOwned {
+    /// The collection (None) or single record (Some) path for this type.
+    fn path(id: Option<String>) -> TaxiiResource;
+    fn id(&self) -> Option<&str>;
+}
+
+/// /taxiiconfig/discoveryinfo. Debug output leaves out the password.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct TaxiiDiscovery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    pub name: String,
+    /// Discovery service (1.1) or discovery endpoint (2.1) URL.
+    pub url: String,
+    pub version: TaxiiVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Sent when saving; FMC never returns it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /taxiiconfig/collections
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaxiiCollection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    pub name: String,
+    /// The collection's ID on the TAXII server; its name in TAXII 1.1.
+    #[serde(rename = "collectionId")]
+    pub collection_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Where content is fetched: the 1.1 poll service or the 2.1 objects URL.
+    pub url: String,
+    pub version: TaxiiVersion,
+    /// The saved TaxiiDiscovery this collection was found through.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<Reference>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum TaxiiVersion {
+    V11,
+    V21,
+    Other(String),
+}
+
+/// TAXII settings, from client.taxii().
+#[derive(Debug)]
+pub struct Taxii<'c> {
+    pub(super) client: &'c FmcClient,
+}
+
+/// Operations on one record type, from client.taxii().collections() etc.
+#[derive(Debug)]
+pub struct TaxiiHandle<'c, T> {
+    pub(super) client: &'c FmcClient,
+    pub(super) record: PhantomData<T>,
+}
@@ -0,0 +1,10 @@
+use super::client::client_schema::{FmcClient, Paginator};
+use super::error::error_schema::FmcError;
+use super::json::json_schema::core::{self, Extra, Reference};
+use super::objects::objects_schema::ObjectList;
+use super::uri::uri_schema::{FmcApi, FmcQuery, TidResource};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+mod tid_methods;
+pub mod tid_schema;
@@ -0,0 +1,455 @@
+use super::tid_schema::{Delivery, IncidentStatus, ObservableType, SourceParams, SourceType};
+use super::tid_schema::{Publishable, Tid, TidAction, TidHandle, TidObject, WritableTidObject};
+use super::tid_schema::{TidElement, TidIncident, TidIndicator, TidObservable, TidSource};
+use super::{Extra, FmcApi, FmcClient, FmcError, FmcQuery, ObjectList, Paginator, TidResource};
+use std::marker::PhantomData;
+
+impl FmcClient {
+    pub fn tid(&self) -> Tid<'_> {
+        Tid { client: self }
+    }
+}
+
+impl<'c> Tid<'c> {
+    pub fn sources(&self) -> TidHandle<'c, TidSource> {
+        self.handle()
+    }
+
+    pub fn indicators(&self) -> TidHandle<'c, TidIndicator> {
+        self.handle()
+    }
+
+    pub fn observables(&self) -> TidHandle<'c, TidObservable> {
+        self.handle()
+    }
+
+    pub fn incidents(&self) -> TidHandle<'c, TidIncident> {
+        self.handle()
+    }
+
+    /// Devices TID publishes to. Read-only.
+    pub fn elements(&self) -> TidHandle<'c, TidElement> {
+        self.handle()
+    }
+
+    fn handle<T: TidObject>(&self) -> TidHandle<'c, T> {
+        TidHandle {
+            client: self.client,
+            record: PhantomData,
+        }
+    }
+}
+
+impl<'c, T: TidObject + 'c> TidHandle<'c, T> {
+    /// Every record of this type, expanded to full records: summaries
+    /// leave out fields the models need.
+    pub fn list(&self) -> Paginator<'c, ObjectList<T>> {
+        self.client
+            .paginate(FmcApi::TidResources(T::path(None)))
+            .expanded(true)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<T, FmcError> {
+        self.client
+            .get_json(FmcApi::TidResources(T::path(Some(id.to_string()))))
+            .await
+    }
+}
+
+impl<'c, T: WritableTidObject + 'c> TidHandle<'c, T> {
+    /// Replaces the record with the same ID.
+    pub async fn update(&self, record: &T) -> Result<T, FmcError> {
+        let id = match record.id() {
+            Some(id) => id.to_string(),
+            None => return Err(FmcError::MissingField("id")),
+        };
+        self.client
+            .put_json(FmcApi::TidResources(T::path(Some(id))), record)
+            .await
+    }
+}
+
+impl<'c, T: Publishable + 'c> TidHandle<'c, T> {
+    /// Pushes the record to managed devices.
+    pub async fn publish(&self, id: &str) -> Result<T, FmcError> {
+        self.set_published(id, true).await
+    }
+
+    /// Withdraws the record from managed devices; FMC keeps it.
+    pub async fn unpublish(&self, id: &str) -> Result<T, FmcError> {
+        self.set_published(id, false).await
+    }
+
+    async fn set_published(&self, id: &str, published: bool) -> Result<T, FmcError> {
+        let mut record = self.get(id).await?;
+        record.set_published(published);
+        self.update(&record).await
+    }
+}
+
+impl<'c> TidHandle<'c, TidSource> {
+    /// Adds a source. For a file, build it with TidSource::upload().
+    pub async fn create(&self, source: &TidSource) -> Result<TidSource, FmcError> {
+        self.client
+            .post_json(
+                FmcApi::TidResources(TidResource::Sources(None)),
+                FmcQuery::new(),
+                source,
+            )
+            .await
+    }
+
+    /// Removes the source and the indicators it brought in.
+    pub async fn delete(&self, id: &str) -> Result<TidSource, FmcError> {
+        self.client
+            .delete_json(FmcApi::TidResources(TidResource::Sources(Some(
+                id.to_string(),
+            ))))
+            .await
+    }
+}
+
+impl<'c> TidHandle<'c, TidIncident> {
+    pub async fn delete(&self, id: &str) -> Result<TidIncident, FmcError> {
+        self.client
+            .delete_json(FmcApi::TidResources(TidResource::Incidents(Some(
+                id.to_string(),
+            ))))
+            .await
+    }
+
+    pub async fn set_status(
+        &self,
+        id: &str,
+        status: IncidentStatus,
+    ) -> Result<TidIncident, FmcError> {
+        let mut incident = self.get(id).await?;
+        incident.status = Some(status);
+        self.update(&incident).await
+    }
+}
+
+impl TidSource {
+    /// A feed FMC fetches from `uri` every `refresh` minutes.
+    pub fn url(name: &str, source_type: SourceType, uri: &str, refresh: u32) -> TidSource {
+        TidSource {
+            uri: Some(uri.to_string()),
+            refresh: Some(refresh),
+            ..TidSource::new(name, source_type, Delivery::Url)
+        }
+    }
+
+    /// A one-off upload of a STIX document or flat file. A flat file
+    /// also needs .observable_type().
+    pub fn upload(name: &str, source_type: SourceType, content: &str) -> TidSource {
+        TidSource {
+            feed_content: Some(content.to_string()),
+            ..TidSource::new(name, source_type, Delivery::Upload)
+        }
+    }
+
+    fn new(name: &str, source_type: SourceType, delivery: Delivery) -> TidSource {
+        TidSource {
+            id: None,
+            _type: source_type,
+            name: name.to_string(),
+            description: None,
+            delivery,
+            uri: None,
+            feed_content: None,
+            params: None,
+            refresh: None,
+            action: None,
+            published: None,
+            ttl: None,
+            links: None,
+            extra: Extra::new(),
+        }
+    }
+
+    /// What each line of a flat file holds, e.g. ObservableType::Ipv4.
+    pub fn observable_type(mut self, observable_type: ObservableType) -> TidSource {
+        self.params = Some(SourceParams {
+            observable_type: Some(observable_type),
+            extra: Extra::new(),
+        });
+        self
+    }
+
+    pub fn action(mut self, action: TidAction) -> TidSource {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn published(mut self, published: bool) -> TidSource {
+        self.published = Some(published);
+        self
+    }
+}
+
+impl TidObject for TidSource {
+    fn path(id: Option<String>) -> TidResource {
+        TidResource::Sources(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+impl WritableTidObject for TidSource {}
+
+impl Publishable for TidSource {
+    fn set_published(&mut self, published: bool) {
+        self.published = Some(published);
+    }
+}
+
+impl TidObject for TidIndicator {
+    fn path(id: Option<String>) -> TidResource {
+        TidResource::Indicators(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl WritableTidObject for TidIndicator {}
+
+impl Publishable for TidIndicator {
+    fn set_published(&mut self, published: bool) {
+        self.published = Some(published);
+    }
+}
+
+impl TidObject for TidObservable {
+    fn path(id: Option<String>) -> TidResource {
+        TidResource::Observables(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl WritableTidObject for TidObservable {}
+
+impl Publishable for TidObservable {
+    fn set_published(&mut self, published: bool) {
+        self.published = Some(published);
+    }
+}
+
+impl TidObject for TidIncident {
+    fn path(id: Option<String>) -> TidResource {
+        TidResource::Incidents(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl WritableTidObject for TidIncident {}
+
+impl TidObject for TidElement {
+    fn path(id: Option<String>) -> TidResource {
+        TidResource::Elements(id)
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl From<String> for SourceType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "stix" => SourceType::Stix,
+            "flatfile" => SourceType::FlatFile,
+            "taxii" => SourceType::Taxii,
+            _ => SourceType::Other(value),
+        }
+    }
+}
+
+impl From<SourceType> for String {
+    fn from(value: SourceType) -> Self {
+        match value {
+            SourceType::Stix => "stix".to_string(),
+            SourceType::FlatFile => "flatfile".to_string(),
+            SourceType::Taxii => "taxii".to_string(),
+            SourceType::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for Delivery {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "url" => Delivery::Url,
+            "upload" => Delivery::Upload,
+            _ => Delivery::Other(value),
+        }
+    }
+}
+
+impl From<Delivery> for String {
+    fn from(value: Delivery) -> Self {
+        match value {
+            Delivery::Url => "url".to_string(),
+            Delivery::Upload => "upload".to_string(),
+            Delivery::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for TidAction {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "monitor" => TidAction::Monitor,
+            "block" => TidAction::Block,
+            _ => TidAction::Other(value),
+        }
+    }
+}
+
+impl From<TidAction> for String {
+    fn from(value: TidAction) -> Self {
+        match value {
+            TidAction::Monitor => "monitor".to_string(),
+            TidAction::Block => "block".to_string(),
+            TidAction::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for ObservableType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "IPV_4_ADDR" => ObservableType::Ipv4,
+            "IPV_6_ADDR" => ObservableType::Ipv6,
+            "URL" => ObservableType::Url,
+            "DOMAIN_NAME" => ObservableType::Domain,
+            "EMAIL_ADDR" => ObservableType::Email,
+            "SHA_256" => ObservableType::Sha256,
+            "FILE_NAME" => ObservableType::FileName,
+            _ => ObservableType::Other(value),
+        }
+    }
+}
+
+impl From<ObservableType> for String {
+    fn from(value: ObservableType) -> Self {
+        match value {
+            ObservableType::Ipv4 => "IPV_4_ADDR".to_string(),
+            ObservableType::Ipv6 => "IPV_6_ADDR".to_string(),
+            ObservableType::Url => "URL".to_string(),
+            ObservableType::Domain => "DOMAIN_NAME".to_string(),
+            ObservableType::Email => "EMAIL_ADDR".to_string(),
+            ObservableType::Sha256 => "SHA_256".to_string(),
+            ObservableType::FileName => "FILE_NAME".to_string(),
+            ObservableType::Other(value) => value,
+        }
+    }
+}
+
+impl From<String> for IncidentStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "new" => IncidentStatus::New,
+            "open" => IncidentStatus::Open,
+            "closed" => IncidentStatus::Closed,
+            _ => IncidentStatus::Other(value),
+        }
+    }
+}
+
+impl From<IncidentStatus> for String {
+    fn from(value: IncidentStatus) -> Self {
+        match value {
+            IncidentStatus::New => "new".to_string(),
+            IncidentStatus::Open => "open".to_string(),
+            IncidentStatus::Closed => "closed".to_string(),
+            IncidentStatus::Other(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::*;
+
+    #[test]
+    fn fn_upload_source() {
+        let source = TidSource::upload("pipeline-ips", SourceType::FlatFile, "203.0.113.7\n")
+            .observable_type(ObservableType::Ipv4)
+            .action(TidAction::Block)
+            .published(true);
+
+        assert_eq!(
+            serde_json::to_value(&source).unwrap(),
+            serde_json::json!({
+                "type": "flatfile",
+                "name": "pipeline-ips",
+                "delivery": "upload",
+                "feedContent": "203.0.113.7\n",
+                "params": {"observableType": "IPV_4_ADDR"},
+                "action": "block",
+                "published": true,
+            })
+        );
+
+        let observable: TidObservable = serde_json::from_str(
+            r#"{"id": "obs-1", "observableType": "JA3_HASH", "value": "e7d7...", "action": "monitor"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            observable.observable_type,
+            Some(ObservableType::Other("JA3_HASH".to_string()))
+        );
+        assert_eq!(observable.action, Some(TidAction::Monitor));
+    }
+
+    #[tokio::test]
+    async fn fn_publish_observables() -> Result<(), FmcError> {
+        let mock = MockFmc::start().await;
+        let client = mock.client().await;
+        let tid = client.tid();
+
+        let source = TidSource::upload("pipeline-ips", SourceType::FlatFile, "203.0.113.7\n")
+            .observable_type(ObservableType::Ipv4);
+        let created = tid.sources().create(&source).await?;
+        let id = created.id.clone().unwrap();
+        assert_eq!(tid.sources().get(&id).await?.delivery, Delivery::Upload);
+        let sources = tid.sources().list().collect_all().await?;
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].delivery, Delivery::Upload);
+
+        let observables = tid.observables().list().collect_all().await?;
+        assert_eq!(observables.len(), 2);
+        assert_eq!(observables[0].published, Some(false));
+
+        let published = tid.observables().publish(&observables[0].id).await?;
+        assert_eq!(published.published, Some(true));
+        assert_eq!(
+            tid.observables().get(&observables[0].id).await?.published,
+            Some(true)
+        );
+        let withdrawn = tid.observables().unpublish(&observables[0].id).await?;
+        assert_eq!(withdrawn.published, Some(false));
+
+        let incidents = tid.incidents().list().collect_all().await?;
+        let closed = tid
+            .incidents()
+            .set_status(&incidents[0].id, IncidentStatus::Closed)
+            .await?;
+        assert_eq!(closed.status, Some(IncidentStatus::Closed));
+
+        assert_eq!(tid.elements().list().collect_all().await?.len(), 1);
+        tid.sources().delete(&id).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use super::{core, DeserializeOwned, Extra, FmcClient, Reference, TidResource};
+use super::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// A record type under /tid, e.g. TidObservable.
+pub trait TidObject: Serialize + DeserializeOwned {
+    /// The collection (None) or single record (Some) path for this type.
+    fn path(id: Option<String>) -> TidResource;
+    fn id(&self) -> Option<&str>;
+}
+
+/// TID records FMC lets you change with PUT.
+pub trait WritableTidObject: TidObject {}
+
+/// TID records that can be published to (or withdrawn from) managed devices.
+pub trait Publishable: WritableTidObject {
+    fn set_published(&mut self, published: bool);
+}
+
+// /tid/source
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TidSource {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Feed format.
+    #[serde(rename = "type")]
+    pub _type: SourceType,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub delivery: Delivery,
+    /// Feed URL, for url and TAXII delivery.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// The feed itself, for upload delivery.
+    #[serde(rename = "feedContent", skip_serializing_if = "Option::is_none")]
+    pub feed_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<SourceParams>,
+    /// Minutes between fetches of a url or TAXII feed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<TidAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    /// Days FMC keeps the feed's indicators.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SourceParams {
+    /// What each line of a flat file holds.
+    #[serde(rename = "observableType", skip_serializing_if = "Option::is_none")]
+    pub observable_type: Option<ObservableType>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /tid/indicator
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TidIndicator {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Reference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<TidAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /tid/observable
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TidObservable {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(rename = "observableType", skip_serializing_if = "Option::is_none")]
+    pub observable_type: Option<ObservableType>,
+    /// The IOC itself, e.g. "203.0.113.7" or "bad.example.com".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<TidAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<bool>,
+    #[serde(rename = "indicatorCount", skip_serializing_if = "Option::is_none")]
+    pub indicator_count: Option<u32>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /tid/incident
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TidIncident {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<IncidentStatus>,
+    #[serde(rename = "actionTaken", skip_serializing_if = "Option::is_none")]
+    pub action_taken: Option<String>,
+    /// The indicator that matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indicator: Option<Reference>,
+    /// The device that saw the traffic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element: Option<Reference>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// /tid/element: managed devices that receive published observables.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TidElement {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "elementType", skip_serializing_if = "Option::is_none")]
+    pub element_type: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub links: Option<core::Links>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Feed format of a TID source. Unknown formats land in Other.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum SourceType {
+    Stix,
+    FlatFile,
+    Taxii,
+    Other(String),
+}
+
+/// How FMC gets a source's feed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum Delivery {
+    /// Fetched from `uri` every `refresh` minutes.
+    Url,
+    /// Sent once in `feedContent`.
+    Upload,
+    Other(String),
+}
+
+/// What devices do with traffic matching a published observable.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum TidAction {
+    Monitor,
+    Block,
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum ObservableType {
+    Ipv4,
+    Ipv6,
+    Url,
+    Domain,
+    Email,
+    Sha256,
+    FileName,
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "String", into = "String")]
+pub enum IncidentStatus {
+    New,
+    Open,
+    Closed,
+    Other(String),
+}
+
+/// Threat intelligence director: /api/fmc_tid/v1/domain/{uuid}/tid
+#[derive(Debug)]
+pub struct Tid<'c> {
+    pub(super) client: &'c FmcClient,
+}
+
+/// Operations on one TID record type, from client.tid().observables() etc.
+#[derive(Debug)]
+pub struct TidHandle<'c, T> {
+    pub(super) client: &'c FmcClient,
+    pub(super) record: PhantomData<T>,
+}
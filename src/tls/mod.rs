@@ -0,0 +1,12 @@
+use super::error::error_schema::FmcError;
+use hyper::client::connect::{Connected, Connection};
+use hyper::client::HttpConnector;
+use hyper::service::Service;
+use hyper::Uri;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+
+mod tls_methods;
+pub mod tls_schema;
+
+pub use tls_methods::sha256_fingerprint;
@@ -0,0 +1,321 @@
+use super::tls_schema::{FmcConnector, FmcStream, TlsConfig};
+use super::Uri;
+use super::{AsyncRead, AsyncWrite, Connected, Connection, FmcError, HttpConnector, Service};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+impl TlsConfig {
+    pub fn new() -> TlsConfig {
+        TlsConfig::default()
+    }
+
+    /// Trusts every certificate in a PEM bundle, e.g. an internal CA.
+    pub fn root_ca_pem(mut self, pem: &[u8]) -> Result<TlsConfig, FmcError> {
+        let pem = String::from_utf8_lossy(pem);
+        let blocks: Vec<&str> = pem
+            .split_inclusive(PEM_END)
+            .filter(|block| block.contains(PEM_END))
+            .collect();
+        if blocks.is_empty() {
+            return Err(FmcError::InvalidCertificate(
+                "no PEM certificate block".to_string(),
+            ));
+        }
+
+        for block in blocks {
+            let cert = native_tls::Certificate::from_pem(block.trim().as_bytes())
+                .map_err(|e| FmcError::InvalidCertificate(e.to_string()))?;
+            self.root_certs.push(cert);
+        }
+        Ok(self)
+    }
+
+    /// Accepts only a server certificate with this SHA-256 fingerprint,
+    /// as hex with or without colons. Pinning replaces chain and hostname
+    /// checks, so it works with FMC's factory self-signed certificate.
+    /// May be called more than once, e.g. across a certificate rollover.
+    pub fn pin_sha256(mut self, fingerprint: &str) -> Result<TlsConfig, FmcError> {
+        let hex: String = fingerprint
+            .chars()
+            .filter(|c| *c != ':' && !c.is_whitespace())
+            .collect();
+        let invalid = || FmcError::InvalidFingerprint(fingerprint.to_string());
+
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut pin = [0u8; 32];
+        for (i, byte) in pin.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        self.pins.push(pin);
+        Ok(self)
+    }
+
+    /// Presents a client certificate from a PEM certificate chain and
+    /// PKCS #8 PEM key.
+    pub fn identity_pem(mut self, cert_pem: &[u8], key_pem: &[u8]) -> Result<TlsConfig, FmcError> {
+        self.identity = Some(native_tls::Identity::from_pkcs8(cert_pem, key_pem)?);
+        Ok(self)
+    }
+
+    /// Presents a client certificate from a PKCS #12 archive.
+    pub fn identity_pkcs12(mut self, der: &[u8], password: &str) -> Result<TlsConfig, FmcError> {
+        self.identity = Some(native_tls::Identity::from_pkcs12(der, password)?);
+        Ok(self)
+    }
+
+    /// Skips certificate and hostname verification entirely. Anyone on the
+    /// path can read the API credentials; prefer root_ca_pem() or
+    /// pin_sha256(), even in labs.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> TlsConfig {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    pub(crate) fn connector(&self) -> Result<FmcConnector, FmcError> {
+        let mut tls = native_tls::TlsConnector::builder();
+        for cert in &self.root_certs {
+            tls.add_root_certificate(cert.clone());
+        }
+        if let Some(identity) = &self.identity {
+            tls.identity(identity.clone());
+        }
+        // A pin is checked after the handshake instead of the chain.
+        if self.accept_invalid_certs || !self.pins.is_empty() {
+            tls.danger_accept_invalid_certs(true);
+            tls.danger_accept_invalid_hostnames(true);
+        }
+
+        Ok(FmcConnector::from_parts(tls.build()?, self.pins.clone()))
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("root_certs", &self.root_certs.len())
+            .field("pins", &self.pins.len())
+            .field("identity", &self.identity.is_some())
+            .field("accept_invalid_certs", &self.accept_invalid_certs)
+            .finish()
+    }
+}
+
+impl FmcConnector {
+    /// System roots, no pins. Fails with FmcError::Tls if the TLS backend
+    /// can't initialize.
+    pub fn new() -> Result<FmcConnector, FmcError> {
+        let tls = native_tls::TlsConnector::new()?;
+        Ok(FmcConnector::from_parts(tls, Vec::new()))
+    }
+
+    fn from_parts(tls: native_tls::TlsConnector, pins: Vec<[u8; 32]>) -> FmcConnector {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        FmcConnector {
+            http,
+            tls: tls.into(),
+            pins: Arc::new(pins),
+        }
+    }
+}
+
+impl fmt::Debug for FmcConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FmcConnector")
+            .field("pins", &self.pins.len())
+            .finish()
+    }
+}
+
+impl Service<Uri> for FmcConnector {
+    type Response = FmcStream;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<FmcStream, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let is_https = dst.scheme_str() == Some("https");
+        let domain = dst
+            .host()
+            .unwrap_or("")
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let connecting = self.http.call(dst);
+        let tls = self.tls.clone();
+        let pins = self.pins.clone();
+
+        Box::pin(async move {
+            let tcp = connecting.await?;
+            if !is_https {
+                return Ok(FmcStream::Http(tcp));
+            }
+
+            let stream = tls.connect(&domain, tcp).await?;
+            if !pins.is_empty() {
+                let der = match stream.get_ref().peer_certificate()? {
+                    Some(cert) => cert.to_der()?,
+                    None => return Err(FmcError::CertificateMismatch("none".to_string()).into()),
+                };
+                let seen: [u8; 32] = Sha256::digest(&der).into();
+                if !pins.contains(&seen) {
+                    return Err(FmcError::CertificateMismatch(sha256_fingerprint(&der)).into());
+                }
+            }
+            Ok(FmcStream::Https(stream))
+        })
+    }
+}
+
+impl AsyncRead for FmcStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            FmcStream::Http(stream) => Pin::new(stream).poll_read(cx, buf),
+            FmcStream::Https(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for FmcStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            FmcStream::Http(stream) => Pin::new(stream).poll_write(cx, buf),
+            FmcStream::Https(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            FmcStream::Http(stream) => Pin::new(stream).poll_flush(cx),
+            FmcStream::Https(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            FmcStream::Http(stream) => Pin::new(stream).poll_shutdown(cx),
+            FmcStream::Https(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+impl Connection for FmcStream {
+    fn connected(&self) -> Connected {
+        match self {
+            FmcStream::Http(stream) => stream.connected(),
+            FmcStream::Https(stream) => stream.get_ref().get_ref().get_ref().connected(),
+        }
+    }
+}
+
+/// "AB:CD:..." SHA-256 fingerprint of a DER certificate, as pin_sha256()
+/// accepts it and `openssl x509 -fingerprint -sha256` prints it.
+pub fn sha256_fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::client::client_schema::FmcClient;
+    use super::super::super::mock::mock_schema::MockFmc;
+    use super::super::super::mock::{MOCK_CA_PEM, MOCK_CERT_PEM, MOCK_PASSWORD, MOCK_USERNAME};
+    use super::*;
+    use std::error::Error;
+
+    fn mock_fingerprint() -> String {
+        let cert = native_tls::Certificate::from_pem(MOCK_CERT_PEM).unwrap();
+        sha256_fingerprint(&cert.to_der().unwrap())
+    }
+
+    #[test]
+    fn fn_pin_sha256() {
+        let pin = mock_fingerprint();
+        assert_eq!(pin.len(), 95);
+
+        let tls = TlsConfig::new().pin_sha256(&pin).unwrap();
+        let tls = tls
+            .pin_sha256(&pin.replace(':', "").to_lowercase())
+            .unwrap();
+        assert_eq!(tls.pins[0], tls.pins[1]);
+
+        for bad in &["", "AB:CD", &pin.replace('A', "G")] {
+            assert!(matches!(
+                TlsConfig::new().pin_sha256(bad),
+                Err(FmcError::InvalidFingerprint(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn fn_root_ca_bundle() {
+        let bundle = [MOCK_CA_PEM, MOCK_CERT_PEM].concat();
+        let tls = TlsConfig::new().root_ca_pem(&bundle).unwrap();
+        assert_eq!(tls.root_certs.len(), 2);
+
+        assert!(matches!(
+            TlsConfig::new().root_ca_pem(b"not a certificate"),
+            Err(FmcError::InvalidCertificate(_))
+        ));
+        let corrupt = "-----BEGIN CERTIFICATE-----\nbm90IGEgY2VydA==\n-----END CERTIFICATE-----\n";
+        assert!(matches!(
+            TlsConfig::new().root_ca_pem(&[MOCK_CA_PEM, corrupt.as_bytes()].concat()),
+            Err(FmcError::InvalidCertificate(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fn_verify_modes() -> Result<(), FmcError> {
+        let mock = MockFmc::start_tls().await;
+        let url = mock.url();
+        let client = |tls| FmcClient::with_tls(&url, MOCK_USERNAME, MOCK_PASSWORD, tls);
+
+        // The mock's CA is not a system root.
+        let err = FmcClient::new(&url, MOCK_USERNAME, MOCK_PASSWORD)
+            .await?
+            .login()
+            .await;
+        assert!(matches!(err, Err(FmcError::Transport(_))));
+
+        mock.client().await.login().await?;
+        let tls = TlsConfig::new().danger_accept_invalid_certs(true);
+        client(tls).await?.login().await?;
+        let tls = TlsConfig::new().pin_sha256(&mock_fingerprint())?;
+        client(tls).await?.login().await?;
+
+        let tls = TlsConfig::new().pin_sha256(&"AB".repeat(32))?;
+        let err = client(tls).await?.login().await.unwrap_err();
+        let mut source = err.source();
+        while let Some(e) = source {
+            if let Some(FmcError::CertificateMismatch(seen)) = e.downcast_ref::<FmcError>() {
+                assert_eq!(seen, &mock_fingerprint());
+                return Ok(());
+            }
+            source = e.source();
+        }
+        panic!("expected a certificate mismatch, got {:?}", err);
+    }
+}
@@ -0,0 +1,29 @@
+use super::{HttpConnector, TcpStream};
+use std::sync::Arc;
+
+/// How the client verifies the FMC's certificate, and which certificate
+/// it presents itself. TlsConfig::new() trusts the system roots only.
+#[derive(Clone, Default)]
+pub struct TlsConfig {
+    pub(super) root_certs: Vec<native_tls::Certificate>,
+    pub(super) pins: Vec<[u8; 32]>,
+    pub(super) identity: Option<native_tls::Identity>,
+    pub(super) accept_invalid_certs: bool,
+}
+
+/// hyper connector for http:// and https:// FMC URLs. Pinned fingerprints
+/// are checked against the certificate of the very session that carries
+/// the request.
+#[derive(Clone)]
+pub struct FmcConnector {
+    pub(super) http: HttpConnector,
+    pub(super) tls: tokio_native_tls::TlsConnector,
+    pub(super) pins: Arc<Vec<[u8; 32]>>,
+}
+
+/// A connection made by FmcConnector.
+#[derive(Debug)]
+pub enum FmcStream {
+    Http(TcpStream),
+    Https(tokio_native_tls::TlsStream<TcpStream>),
+}
@@ -1,27 +1,42 @@
-use super::uri_schema::FmcApi;
-use super::{FMCUri, Uuid}; // Local
+use super::info::info_schema::Version;
+use super::uri_schema::{AccessPolicyPath, DevicePath, Filter, FmcApi, FmcQuery, Object};
+use super::uri_schema::{TaxiiResource, TidResource};
+use super::{FMCUri, FmcError, Uuid}; // Local
 
 impl FmcApi {
     pub(super) async fn path_vec(
         self,
         address: Option<&str>,
         dom_uuid: Option<Uuid>,
-    ) -> Vec<String> {
+    ) -> Result<Vec<String>, FmcError> {
         let mut path: Vec<String> = Vec::new();
-        path.push("https://".to_string());
-        path.push(address.unwrap().to_string());
-        path.push(":443".to_string());
+        match address {
+            // A full base URL, e.g. https://fmc:8443 or a local mock over http.
+            Some(address) if address.contains("://") => {
+                path.push(address.trim_end_matches('/').to_string())
+            }
+            Some(address) => {
+                path.push("https://".to_string());
+                path.push(address.to_string());
+                path.push(":443".to_string());
+            }
+            None => return Err(FmcError::MissingField("host")),
+        }
 
         let dom_uuid = match dom_uuid {
             Some(uuid) => uuid.to_string(),
             None => "".to_string(),
         };
 
-        match self {
+        let path = match self {
             Self::HttpBasicAuth => {
                 path.push("/api/fmc_platform/v1/auth/generatetoken".to_string());
                 path
             }
+            Self::RefreshToken => {
+                path.push("/api/fmc_platform/v1/auth/refreshtoken".to_string());
+                path
+            }
             Self::Devices => {
                 path.push("/api/fmc_config/v1/domain/".to_string());
                 path.push(dom_uuid);
@@ -61,7 +76,7 @@ impl FmcApi {
             Self::DeviceClusters => {
                 path.push("/api/fmc_config/v1/domain/".to_string());
                 path.push(dom_uuid);
-                path.push("/devices".to_string());
+                path.push("/deviceclusters/ftddevicecluster".to_string());
                 path
             }
             Self::Object => {
@@ -96,22 +111,543 @@ impl FmcApi {
                 path.push("/api/fmc_platform/v1/info".to_string());
                 path
             }
+            Self::Domains => {
+                path.push("/api/fmc_platform/v1/info/domain".to_string());
+                path
+            }
+            Self::ServerVersion => {
+                path.push("/api/fmc_platform/v1/info/serverversion".to_string());
+                path
+            }
             Self::TaxiiConfig => {
                 path.push("/api/fmc_tid/v1/domain/".to_string());
                 path.push(dom_uuid.to_string());
                 path.push("/taxiiconfig".to_string());
                 path
             }
+            Self::TaxiiResources(resource) => {
+                path.push("/api/fmc_tid/v1/domain/".to_string());
+                path.push(dom_uuid.to_string());
+                path.push("/taxiiconfig/".to_string());
+                let (kind, id) = resource.parts();
+                path.push(kind.to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
             Self::Tid => {
                 path.push("/api/fmc_tid/v1/domain/".to_string());
                 path.push(dom_uuid.to_string());
                 path.push("/tid".to_string());
                 path
             }
+            Self::TidResources(resource) => {
+                path.push("/api/fmc_tid/v1/domain/".to_string());
+                path.push(dom_uuid.to_string());
+                path.push("/tid/".to_string());
+                let (kind, id) = resource.parts();
+                path.push(kind.to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::Device(device) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devices/devicerecords".to_string());
+                path.push(id_segment(&device)?);
+                path
+            }
+            Self::DeviceHAPair(pair) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devicehapairs/ftddevicehapairs".to_string());
+                path.push(id_segment(&pair)?);
+                path
+            }
+            Self::DeviceCluster(cluster) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/deviceclusters/ftddevicecluster".to_string());
+                path.push(id_segment(&cluster)?);
+                path
+            }
+            Self::DeviceGroup(group) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devicegroups/devicegrouprecords".to_string());
+                path.push(id_segment(&group)?);
+                path
+            }
+            Self::HAMonitoredInterfaces(pair, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devicehapairs/ftddevicehapairs".to_string());
+                path.push(id_segment(&pair)?);
+                path.push("/monitoredinterfaces".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::PhysicalInterfaces(device, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devices/devicerecords".to_string());
+                path.push(id_segment(&device)?);
+                path.push("/physicalinterfaces".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::EtherChannelInterfaces(device, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/devices/devicerecords".to_string());
+                path.push(id_segment(&device)?);
+                path.push("/etherchannelinterfaces".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::Objects(object) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/object/".to_string());
+                let (kind, id) = object.parts();
+                path.push(kind.to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::AccessPolicies(id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/policy/accesspolicies".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::AccessRules(policy, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/policy/accesspolicies".to_string());
+                path.push(id_segment(&policy)?);
+                path.push("/accessrules".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::AccessPolicyCategories(policy, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/policy/accesspolicies".to_string());
+                path.push(id_segment(&policy)?);
+                path.push("/categories".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::AccessPolicyDefaultActions(policy, id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/policy/accesspolicies".to_string());
+                path.push(id_segment(&policy)?);
+                path.push("/defaultactions".to_string());
+                path.push(opt_id_segment(id)?);
+                path
+            }
+            Self::DeployableDevices => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/deployment/deployabledevices".to_string());
+                path
+            }
+            Self::DeploymentRequests => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/deployment/deploymentrequests".to_string());
+                path
+            }
+            Self::TaskStatus(id) => {
+                path.push("/api/fmc_config/v1/domain/".to_string());
+                path.push(dom_uuid);
+                path.push("/taskstatuses".to_string());
+                path.push(id_segment(&id)?);
+                path
+            }
+        };
+        Ok(path)
+    }
+
+    pub(crate) async fn path_string(
+        self,
+        address: Option<&str>,
+        dom_uuid: Option<Uuid>,
+    ) -> Result<FMCUri, FmcError> {
+        Ok(self.path_vec(address, dom_uuid).await?.join(""))
+    }
+
+    /// Addresses one device record; chain .physical_interfaces() etc.
+    pub fn device(id: &str) -> DevicePath {
+        DevicePath(id.to_string())
+    }
+
+    /// Addresses one access policy; chain .access_rules() etc.
+    pub fn access_policy(id: &str) -> AccessPolicyPath {
+        AccessPolicyPath(id.to_string())
+    }
+
+    /// The first FMC release serving this endpoint, for those added after
+    /// the REST API itself (6.1). Requests through an FmcClient check it.
+    pub fn min_version(&self) -> Option<Version> {
+        let min = match self {
+            Self::TaxiiConfig | Self::TaxiiResources(_) | Self::Tid | Self::TidResources(_) => {
+                "6.3"
+            }
+            _ => return None,
+        };
+        min.parse().ok()
+    }
+
+    /// Whether this endpoint understands the given filter clause.
+    pub fn allows_filter(&self, filter: &Filter) -> bool {
+        match filter {
+            Filter::Raw(_, _) => true,
+            Filter::Name(_) => matches!(self, Self::Devices | Self::AccessPolicies(None)),
+            Filter::HostName(_) => matches!(self, Self::Devices),
+            Filter::NameOrValue(_) | Filter::UnusedOnly(_) | Filter::Ids(_) => {
+                matches!(self, Self::Object | Self::Objects(_))
+            }
         }
     }
+}
+
+impl Object {
+    /// The URL segment for this object type and the optional object ID.
+    fn parts(self) -> (&'static str, Option<String>) {
+        match self {
+            Self::Hosts(id) => ("hosts", id),
+            Self::Networks(id) => ("networks", id),
+            Self::Ranges(id) => ("ranges", id),
+            Self::Fqdns(id) => ("fqdns", id),
+            Self::NetworkGroups(id) => ("networkgroups", id),
+            Self::ProtocolPortObjects(id) => ("protocolportobjects", id),
+            Self::PortObjectGroups(id) => ("portobjectgroups", id),
+            Self::IcmpV4Objects(id) => ("icmpv4objects", id),
+            Self::IcmpV6Objects(id) => ("icmpv6objects", id),
+            Self::Ports(id) => ("ports", id),
+        }
+    }
+}
+
+impl TidResource {
+    /// The URL segment for this record type and the optional record ID.
+    fn parts(self) -> (&'static str, Option<String>) {
+        match self {
+            Self::Sources(id) => ("source", id),
+            Self::Indicators(id) => ("indicator", id),
+            Self::Observables(id) => ("observable", id),
+            Self::Incidents(id) => ("incident", id),
+            Self::Elements(id) => ("element", id),
+        }
+    }
+}
+
+impl TaxiiResource {
+    /// The URL segment for this record type and the optional record ID.
+    fn parts(self) -> (&'static str, Option<String>) {
+        match self {
+            Self::Discoveries(id) => ("discoveryinfo", id),
+            Self::Collections(id) => ("collections", id),
+        }
+    }
+}
+
+impl From<TaxiiResource> for FmcApi {
+    fn from(resource: TaxiiResource) -> FmcApi {
+        FmcApi::TaxiiResources(resource)
+    }
+}
+
+impl From<TidResource> for FmcApi {
+    fn from(resource: TidResource) -> FmcApi {
+        FmcApi::TidResources(resource)
+    }
+}
+
+impl From<Object> for FmcApi {
+    fn from(object: Object) -> FmcApi {
+        FmcApi::Objects(object)
+    }
+}
+
+impl DevicePath {
+    /// devices/devicerecords/{id}
+    pub fn record(self) -> FmcApi {
+        FmcApi::Device(self.0)
+    }
+
+    pub fn physical_interfaces(self) -> FmcApi {
+        FmcApi::PhysicalInterfaces(self.0, None)
+    }
+
+    pub fn physical_interface(self, id: &str) -> FmcApi {
+        FmcApi::PhysicalInterfaces(self.0, Some(id.to_string()))
+    }
+
+    pub fn etherchannel_interfaces(self) -> FmcApi {
+        FmcApi::EtherChannelInterfaces(self.0, None)
+    }
+
+    pub fn etherchannel_interface(self, id: &str) -> FmcApi {
+        FmcApi::EtherChannelInterfaces(self.0, Some(id.to_string()))
+    }
+}
+
+impl AccessPolicyPath {
+    /// policy/accesspolicies/{id}
+    pub fn record(self) -> FmcApi {
+        FmcApi::AccessPolicies(Some(self.0))
+    }
+
+    pub fn access_rules(self) -> FmcApi {
+        FmcApi::AccessRules(self.0, None)
+    }
+
+    pub fn access_rule(self, id: &str) -> FmcApi {
+        FmcApi::AccessRules(self.0, Some(id.to_string()))
+    }
+
+    pub fn categories(self) -> FmcApi {
+        FmcApi::AccessPolicyCategories(self.0, None)
+    }
+
+    pub fn category(self, id: &str) -> FmcApi {
+        FmcApi::AccessPolicyCategories(self.0, Some(id.to_string()))
+    }
+
+    pub fn default_actions(self) -> FmcApi {
+        FmcApi::AccessPolicyDefaultActions(self.0, None)
+    }
+
+    pub fn default_action(self, id: &str) -> FmcApi {
+        FmcApi::AccessPolicyDefaultActions(self.0, Some(id.to_string()))
+    }
+}
+
+/// "/{id}" after checking the ID can't escape its path segment.
+/// FMC IDs are UUIDs or short alphanumeric names like "PhyIntfId1".
+fn id_segment(id: &str) -> Result<String, FmcError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && id != "."
+        && id != "..";
+
+    match valid {
+        true => Ok(["/", id].join("")),
+        false => Err(FmcError::InvalidId(id.to_string())),
+    }
+}
+
+fn opt_id_segment(id: Option<String>) -> Result<String, FmcError> {
+    match id {
+        Some(id) => id_segment(&id),
+        None => Ok(String::new()),
+    }
+}
+
+impl FmcQuery {
+    pub fn new() -> FmcQuery {
+        FmcQuery::default()
+    }
+
+    pub fn filter(mut self, filter: Filter) -> FmcQuery {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn offset(mut self, offset: u32) -> FmcQuery {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> FmcQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn expanded(mut self, expanded: bool) -> FmcQuery {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// Any other parameter, e.g. .param("bulk", "true").
+    pub fn param(mut self, key: &str, value: &str) -> FmcQuery {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == FmcQuery::default()
+    }
+
+    /// Renders the query string (without the leading '?') after checking
+    /// every filter clause against the endpoint's filter grammar.
+    pub fn render(&self, api: &FmcApi) -> Result<String, FmcError> {
+        let mut pairs: Vec<String> = Vec::new();
+
+        if !self.filters.is_empty() {
+            let mut clauses: Vec<String> = Vec::new();
+            for filter in &self.filters {
+                if !api.allows_filter(filter) {
+                    return Err(FmcError::UnsupportedFilter(filter.to_string()));
+                }
+                clauses.push(filter.to_string());
+            }
+            pairs.push(["filter", &percent_encode(&clauses.join(";"))].join("="));
+        }
+        if let Some(offset) = self.offset {
+            pairs.push(["offset", &offset.to_string()].join("="));
+        }
+        if let Some(limit) = self.limit {
+            pairs.push(["limit", &limit.to_string()].join("="));
+        }
+        if let Some(expanded) = self.expanded {
+            pairs.push(["expanded", &expanded.to_string()].join("="));
+        }
+        for (key, value) in &self.params {
+            pairs.push([percent_encode(key), percent_encode(value)].join("="));
+        }
+
+        Ok(pairs.join("&"))
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::Name(name) => write!(f, "name:{}", name),
+            Filter::HostName(host) => write!(f, "hostName:{}", host),
+            Filter::NameOrValue(text) => write!(f, "nameOrValue:{}", text),
+            Filter::Ids(ids) => write!(f, "ids:{}", ids.join(",")),
+            Filter::UnusedOnly(unused) => write!(f, "unusedOnly:{}", unused),
+            Filter::Raw(key, value) => write!(f, "{}:{}", key, value),
+        }
+    }
+}
+
+/// Percent-encodes everything outside RFC 3986's unreserved set.
+fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fn_sub_resource_paths() -> Result<(), FmcError> {
+        let dom_uuid = Uuid::parse_str("f3b4958c-52a1-11e7-802a-010203040506").ok();
+        let base =
+            "https://fmc.local:443/api/fmc_config/v1/domain/f3b4958c-52a1-11e7-802a-010203040506";
+
+        assert_eq!(
+            FmcApi::device("dev-1")
+                .physical_interfaces()
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            [base, "/devices/devicerecords/dev-1/physicalinterfaces"].join("")
+        );
+        assert_eq!(
+            FmcApi::device("dev-1")
+                .etherchannel_interface("PhyIntfId1")
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            [
+                base,
+                "/devices/devicerecords/dev-1/etherchannelinterfaces/PhyIntfId1"
+            ]
+            .join("")
+        );
+        assert_eq!(
+            FmcApi::from(Object::Networks(Some("net-1".to_string())))
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            [base, "/object/networks/net-1"].join("")
+        );
+        assert_eq!(
+            FmcApi::DeviceGroup("grp-1".to_string())
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            [base, "/devicegroups/devicegrouprecords/grp-1"].join("")
+        );
+        assert_eq!(
+            FmcApi::access_policy("acp-1")
+                .access_rule("rule-9")
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            [base, "/policy/accesspolicies/acp-1/accessrules/rule-9"].join("")
+        );
+        assert_eq!(
+            FmcApi::from(TaxiiResource::Collections(Some("col-1".to_string())))
+                .path_string(Some("fmc.local"), dom_uuid)
+                .await?,
+            "https://fmc.local:443/api/fmc_tid/v1/domain/f3b4958c-52a1-11e7-802a-010203040506\
+             /taxiiconfig/collections/col-1"
+        );
+
+        assert_eq!(
+            FmcApi::Devices
+                .path_string(Some("http://127.0.0.1:8080/"), dom_uuid)
+                .await?,
+            "http://127.0.0.1:8080/api/fmc_config/v1/domain/f3b4958c-52a1-11e7-802a-010203040506\
+             /devices/devicerecords"
+        );
+
+        let err = FmcApi::device("../../auth")
+            .record()
+            .path_string(Some("fmc.local"), dom_uuid)
+            .await;
+        assert!(matches!(err, Err(FmcError::InvalidId(_))));
+        let err = FmcApi::Objects(Object::Hosts(Some(String::new())))
+            .path_string(Some("fmc.local"), dom_uuid)
+            .await;
+        assert!(matches!(err, Err(FmcError::InvalidId(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn fn_render_query() {
+        let query = FmcQuery::new()
+            .filter(Filter::NameOrValue("10.1.1.0/24".to_string()))
+            .filter(Filter::UnusedOnly(true))
+            .offset(25)
+            .limit(1000)
+            .expanded(true)
+            .param("bulk", "true");
+
+        assert_eq!(
+            query.render(&FmcApi::Object).unwrap(),
+            "filter=nameOrValue%3A10.1.1.0%2F24%3BunusedOnly%3Atrue&offset=25&limit=1000&expanded=true&bulk=true"
+        );
+        assert!(FmcQuery::new().render(&FmcApi::Devices).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fn_filter_grammar() {
+        let query = FmcQuery::new().filter(Filter::Name("fw 01".to_string()));
+        assert_eq!(
+            query.render(&FmcApi::Devices).unwrap(),
+            "filter=name%3Afw%2001"
+        );
+        assert!(matches!(
+            query.render(&FmcApi::Object),
+            Err(FmcError::UnsupportedFilter(_))
+        ));
 
-    pub(crate) async fn path_string(self, address: Option<&str>, dom_uuid: Option<Uuid>) -> FMCUri {
-        self.path_vec(address, dom_uuid).await.join("")
+        let query = FmcQuery::new().filter(Filter::Raw("type".to_string(), "x".to_string()));
+        assert!(query.render(&FmcApi::Audit).is_ok());
     }
 }
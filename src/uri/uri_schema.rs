@@ -1,4 +1,6 @@
-pub(crate) enum FmcApi {
+/// FMC API collections, resolved to full URLs by path_string().
+#[derive(Debug, Clone, PartialEq)]
+pub enum FmcApi {
     //Config: /api​/fmc_config​/v1​/domain​/f3b4958c-52a1-11e7-802a-010203040506​/{type}
     Devices,
     PolicyAssignments,
@@ -11,19 +13,105 @@ pub(crate) enum FmcApi {
     Policy,
     Deployment,
 
+    /// devices/devicerecords/{id}
+    Device(String),
+    /// devicehapairs/ftddevicehapairs/{id}
+    DeviceHAPair(String),
+    /// deviceclusters/ftddevicecluster/{id}
+    DeviceCluster(String),
+    /// devicegroups/devicegrouprecords/{id}
+    DeviceGroup(String),
+    /// devicehapairs/ftddevicehapairs/{pair}/monitoredinterfaces[/{id}]
+    HAMonitoredInterfaces(String, Option<String>),
+    /// devices/devicerecords/{device}/physicalinterfaces[/{id}]
+    PhysicalInterfaces(String, Option<String>),
+    /// devices/devicerecords/{device}/etherchannelinterfaces[/{id}]
+    EtherChannelInterfaces(String, Option<String>),
+    /// object/{type}[/{id}]
+    Objects(Object),
+    /// policy/accesspolicies[/{id}]
+    AccessPolicies(Option<String>),
+    /// policy/accesspolicies/{policy}/accessrules[/{id}]
+    AccessRules(String, Option<String>),
+    /// policy/accesspolicies/{policy}/categories[/{id}]
+    AccessPolicyCategories(String, Option<String>),
+    /// policy/accesspolicies/{policy}/defaultactions[/{id}]
+    AccessPolicyDefaultActions(String, Option<String>),
+    /// deployment/deployabledevices
+    DeployableDevices,
+    /// deployment/deploymentrequests
+    DeploymentRequests,
+    /// taskstatuses/{id}
+    TaskStatus(String),
+
     //Platform: /api/fmc_platform/v1/{type}
     Updates,
     Audit,
     Info,
+    /// info/domain
+    Domains,
+    /// info/serverversion
+    ServerVersion,
 
     //Threat intelligence: /api/fmc_tid/v1/domain/f3b4958c-52a1-11e7-802a-010203040506/{type}
     TaxiiConfig,
+    /// taxiiconfig/{type}[/{id}]
+    TaxiiResources(TaxiiResource),
     Tid,
+    /// tid/{type}[/{id}]
+    TidResources(TidResource),
 
     /// api/fmc_platform/v1/auth/generatetoken.
     HttpBasicAuth,
+
+    /// api/fmc_platform/v1/auth/refreshtoken.
+    RefreshToken,
+}
+
+/// Object collections under /object. Some(id) addresses one object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Hosts(Option<String>),
+    Networks(Option<String>),
+    Ranges(Option<String>),
+    Fqdns(Option<String>),
+    NetworkGroups(Option<String>),
+    ProtocolPortObjects(Option<String>),
+    PortObjectGroups(Option<String>),
+    IcmpV4Objects(Option<String>),
+    IcmpV6Objects(Option<String>),
+    /// Read-only: every port-type object regardless of kind.
+    Ports(Option<String>),
+}
+
+/// Threat intelligence director collections under /tid. Some(id)
+/// addresses one record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TidResource {
+    Sources(Option<String>),
+    Indicators(Option<String>),
+    Observables(Option<String>),
+    Incidents(Option<String>),
+    Elements(Option<String>),
+}
+
+/// TAXII feed settings under /taxiiconfig. Some(id) addresses one record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaxiiResource {
+    /// TAXII servers: discovery URL, version and credentials.
+    Discoveries(Option<String>),
+    /// Collections on those servers that FMC polls.
+    Collections(Option<String>),
 }
 
+/// Chain-style addressing for one device record, from FmcApi::device().
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevicePath(pub(crate) String);
+
+/// Chain-style addressing for one access policy, from FmcApi::access_policy().
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessPolicyPath(pub(crate) String);
+
 /*#[cfg(test)]
 mod tests {
     use super::super::Uuid;
@@ -87,3 +175,32 @@ mod tests {
     }
 }
 */
+
+/// Query string for an FMC request, rendered and percent-encoded by
+/// FmcQuery::render() once the endpoint is known.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FmcQuery {
+    pub(crate) filters: Vec<Filter>,
+    pub(crate) offset: Option<u32>,
+    pub(crate) limit: Option<u32>,
+    pub(crate) expanded: Option<bool>,
+    pub(crate) params: Vec<(String, String)>,
+}
+
+/// One clause of FMC's filter= parameter. Clauses are joined with ';',
+/// e.g. filter=nameOrValue:10.1.1.1;unusedOnly:true
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// name:{name}
+    Name(String),
+    /// hostName:{host}
+    HostName(String),
+    /// nameOrValue:{text}
+    NameOrValue(String),
+    /// ids:{id},{id},...
+    Ids(Vec<String>),
+    /// unusedOnly:{bool}
+    UnusedOnly(bool),
+    /// {key}:{value} for clauses not modelled above. Never rejected.
+    Raw(String, String),
+}